@@ -7,6 +7,14 @@ pub mod consts {
     pub const HOST: &str = "--host";
     pub const P: &str = "-p";
     pub const PORT: &str = "--port";
+    pub const T: &str = "-t";
+    pub const TIP: &str = "--tip";
+    pub const F: &str = "-f";
+    pub const FALLBACK: &str = "--fallback";
+    pub const B: &str = "-b";
+    pub const FEE_BUDGET: &str = "--fee-budget";
+    pub const D: &str = "-d";
+    pub const DRY_RUN: &str = "--dry-run";
 
     pub const PEAQ: &str = "peaq";
     pub const AGUNG: &str = "agung";
@@ -24,11 +32,19 @@ pub enum ArgError {
     UnrecognisedNetwork,
     #[error("Port is not valid")]
     FailedToParsePort,
+    #[error("Tip is not valid")]
+    FailedToParseTip,
+    #[error("Fee budget is not valid")]
+    FailedToParseFeeBudget,
 }
 
 pub struct Network;
 pub struct Host;
 pub struct Port;
+pub struct Tip;
+pub struct Fallback;
+pub struct FeeBudget;
+pub struct DryRun;
 
 impl Network {
     pub fn config(option: String) -> Result<Config, ArgError> {
@@ -62,6 +78,26 @@ impl<'a> IsArgument<'a> for Port {
         vec![consts::P, consts::PORT]
     }
 }
+impl<'a> IsArgument<'a> for Tip {
+    fn predicates(&self) -> Vec<&'a str> {
+        vec![consts::T, consts::TIP]
+    }
+}
+impl<'a> IsArgument<'a> for Fallback {
+    fn predicates(&self) -> Vec<&'a str> {
+        vec![consts::F, consts::FALLBACK]
+    }
+}
+impl<'a> IsArgument<'a> for FeeBudget {
+    fn predicates(&self) -> Vec<&'a str> {
+        vec![consts::B, consts::FEE_BUDGET]
+    }
+}
+impl<'a> IsArgument<'a> for DryRun {
+    fn predicates(&self) -> Vec<&'a str> {
+        vec![consts::D, consts::DRY_RUN]
+    }
+}
 
 #[derive(Debug)]
 pub struct Args(Vec<String>);
@@ -80,6 +116,15 @@ impl Args {
         iter.find(|p| predicates.iter().any(|pred| p == pred));
         (iter.next().cloned(), self)
     }
+
+    /// For flags, which take no value.
+    pub fn contains<'a>(self, predicates: Vec<&'a str>) -> (bool, Self) {
+        let found = self
+            .get()
+            .iter()
+            .any(|p| predicates.iter().any(|pred| p == pred));
+        (found, self)
+    }
 }
 
 trait Parsable<'a> {
@@ -121,6 +166,60 @@ impl<'a> Parsable<'a> for Port {
     }
 }
 
+impl<'a> Parsable<'a> for Tip {
+    type Output = Result<(Option<u128>, Args), ArgError>;
+
+    fn parse(&self, args: Args) -> Self::Output {
+        let (value, args) = args.get_value(self.predicates());
+        match value {
+            Some(value) => match value.parse() {
+                Ok(tip) => Ok((Some(tip), args)),
+                Err(_) => return Err(ArgError::FailedToParseTip),
+            },
+            None => Ok((None, args)),
+        }
+    }
+}
+
+impl<'a> Parsable<'a> for Fallback {
+    type Output = (Option<Vec<String>>, Args);
+
+    fn parse(&self, args: Args) -> Self::Output {
+        let (value, args) = args.get_value(self.predicates());
+        let fallbacks = value.map(|value| {
+            value
+                .split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect()
+        });
+        (fallbacks, args)
+    }
+}
+
+impl<'a> Parsable<'a> for FeeBudget {
+    type Output = Result<(Option<u128>, Args), ArgError>;
+
+    fn parse(&self, args: Args) -> Self::Output {
+        let (value, args) = args.get_value(self.predicates());
+        match value {
+            Some(value) => match value.parse() {
+                Ok(budget) => Ok((Some(budget), args)),
+                Err(_) => return Err(ArgError::FailedToParseFeeBudget),
+            },
+            None => Ok((None, args)),
+        }
+    }
+}
+
+impl<'a> Parsable<'a> for DryRun {
+    type Output = (bool, Args);
+
+    fn parse(&self, args: Args) -> Self::Output {
+        args.contains(self.predicates())
+    }
+}
+
 struct Peaq;
 struct Agung;
 struct Krest;
@@ -133,6 +232,10 @@ pub struct Config {
     pub host: String,
     pub port: u16,
     pub chain_id: u16,
+    pub tip: u128,
+    pub fallbacks: Vec<String>,
+    pub fee_budget: Option<u128>,
+    pub dry_run: bool,
 }
 
 impl<'a> Config {
@@ -154,11 +257,29 @@ impl<'a> Config {
             config.host = host;
         }
 
-        let (port, _args) = Port.parse(args)?;
+        let (port, args) = Port.parse(args)?;
         if let Some(port) = port {
             config.port = port;
         }
 
+        let (tip, args) = Tip.parse(args)?;
+        if let Some(tip) = tip {
+            config.tip = tip;
+        }
+
+        let (fallbacks, args) = Fallback.parse(args);
+        if let Some(fallbacks) = fallbacks {
+            config.fallbacks = fallbacks;
+        }
+
+        let (fee_budget, args) = FeeBudget.parse(args)?;
+        if let Some(fee_budget) = fee_budget {
+            config.fee_budget = Some(fee_budget);
+        }
+
+        let (dry_run, _args) = DryRun.parse(args);
+        config.dry_run = dry_run;
+
         Ok(config)
     }
 }
@@ -174,6 +295,10 @@ impl NetworkConfig for Peaq {
             host: "https://erpc-mpfn1.peaq.network".to_string(),
             port: 443,
             chain_id: 3338,
+            tip: 0,
+            fallbacks: Vec::new(),
+            fee_budget: None,
+            dry_run: false,
         }
     }
 }
@@ -185,6 +310,10 @@ impl NetworkConfig for Krest {
             host: "https://erpc-krest.peaq.network".to_string(),
             port: 443,
             chain_id: 2241,
+            tip: 0,
+            fallbacks: Vec::new(),
+            fee_budget: None,
+            dry_run: false,
         }
     }
 }
@@ -196,6 +325,10 @@ impl NetworkConfig for Agung {
             host: "https://rpcpc1-qa.agung.peaq.network".to_string(),
             port: 443,
             chain_id: 9990,
+            tip: 0,
+            fallbacks: Vec::new(),
+            fee_budget: None,
+            dry_run: false,
         }
     }
 }
@@ -207,6 +340,10 @@ impl NetworkConfig for Dev {
             host: "http://127.0.0.1".to_string(),
             port: 9944,
             chain_id: 9990,
+            tip: 0,
+            fallbacks: Vec::new(),
+            fee_budget: None,
+            dry_run: false,
         }
     }
 }
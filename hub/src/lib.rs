@@ -1,36 +1,78 @@
-use node::calls::{call::Call, extrinsic::Extrinsic};
+use async_std::{channel, task};
+use node::calls::dry_run::DryRunOutcome;
+use node::calls::transport::Transport;
+use node::dispatch::DispatchFailure;
+use node::endpoints::Endpoints;
 use node::errors::NodeError;
-use node::extrinsics::{AddAttribute, ExtrinsicCall};
+use node::events::{ExtrinsicEvents, ExtrinsicOutcome};
+use node::extrinsics::{AddAttribute, AttributeArgs, ExtrinsicCall, SignedExtrinsic, Watched};
+use node::finality::Finality;
+use node::metadata::Metadata;
+use node::nonces::NonceManager;
 use rand::{distributions::Alphanumeric, Rng};
 use rumqttc::{Client, Connection, ConnectionError, Event, Incoming, MqttOptions, Publish, QoS};
 use sp_core::crypto::AccountId32;
-use sp_core::Pair;
+use sp_core::{Pair, H256};
 use sp_keyring::AccountKeyring;
+use std::error::Error;
 use std::time::Duration;
 
 pub mod consts {
+    use node::calls::submit_and_watch::ExtrinsicStage;
+    use std::time::Duration;
+
     pub const CLIENT_ID: &str = "aydo-peaq-client-id";
     pub const BROKER_IP: &str = "127.0.0.1";
     pub const BROKER_PORT: u16 = 1883;
     pub const TARGET: Option<&str> = None;
     pub const TOPIC: &str = "aydo/#";
+    pub const ANCHOR_STAGE: ExtrinsicStage = ExtrinsicStage::InBlock;
+    // A reading only counts as anchored once its block is finalized
+    pub const FINALITY_TIMEOUT: Duration = Duration::from_secs(120);
+    pub const MAX_IN_FLIGHT: usize = 32;
 }
 
-pub async fn run(url: &str) -> Result<(), ConnectionError> {
+/// `fee_budget` is the highest partial fee, tip excluded, a single reading
+/// may cost. With `dry_run` readings are only checked against the chain.
+pub async fn run(
+    url: &str,
+    tip: u128,
+    fee_budget: Option<u128>,
+    dry_run: bool,
+) -> Result<(), ConnectionError> {
     let (client, mut connection) = make_connection();
     client.subscribe(consts::TOPIC, QoS::AtMostOnce).unwrap();
 
+    // One slot per submission in flight, taken before it is spawned
+    let (slots, freed) = channel::bounded::<()>(consts::MAX_IN_FLIGHT);
+
     let mut iter = connection.iter().enumerate();
     while let Some((index, event)) = iter.next() {
         if let Some(publish) = process_event(event?).await {
-            let extrinsic = compose_tx().await;
-            let tx = send_tx(url, extrinsic).await;
-            let _ = print_results(index, publish, tx);
+            let extrinsic = compose_tx().await.tip(tip);
+            let url = url.to_string();
+            let _ = slots.send(()).await;
+            let freed = freed.clone();
+            task::spawn(async move {
+                let tx = send_tx(&url, extrinsic, fee_budget, dry_run).await;
+                print_results(index, publish, tx);
+                let _ = freed.recv().await;
+            });
         }
     }
     Ok(())
 }
 
+fn report(error: &dyn Error) -> String {
+    let mut report = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        report.push_str(&format!("\n{}", cause));
+        source = cause.source();
+    }
+    report
+}
+
 fn make_connection() -> (Client, Connection) {
     let mut mqtt = MqttOptions::new(consts::CLIENT_ID, consts::BROKER_IP, consts::BROKER_PORT);
     mqtt.set_keep_alive(Duration::from_secs(4));
@@ -45,7 +87,10 @@ fn print_results(index: usize, publish: Publish, tx: Result<String, NodeError>)
         publish.topic,
         String::from_utf8(publish.payload.to_vec()).unwrap()
     );
-    println!("↳  TX: {:?}", tx);
+    match tx {
+        Ok(tx) => println!("↳  TX: {}", tx),
+        Err(e) => println!("↳  TX failed ({:?}): {}", e.kind(), report(&e)),
+    }
 }
 
 async fn process_event(event: Event) -> Option<Publish> {
@@ -92,17 +137,161 @@ fn get_name() -> Vec<u8> {
 
 async fn compose_tx() -> node::extrinsics::Extrinsic<AddAttribute> {
     let alice = AccountKeyring::Alice.pair();
-    let did_account = AccountId32::from(alice.public());
-    let name = get_name();
-    let value = Vec::new();
+    let args = AttributeArgs {
+        did_account: AccountId32::from(alice.public()),
+        name: get_name(),
+        value: Vec::new(),
+        valid_for: None,
+    };
 
-    AddAttribute::new(alice, (did_account, name, value, None))
+    AddAttribute::new(alice, args)
 }
 
 async fn send_tx(
     url: &str,
     extrinsic: node::extrinsics::Extrinsic<AddAttribute>,
+    fee_budget: Option<u128>,
+    dry_run: bool,
 ) -> Result<String, NodeError> {
-    let extrinsic_hash = extrinsic.build(url).await?;
-    Ok(Extrinsic.get(url, Some(vec![extrinsic_hash])).await?)
+    check_budget(url, &extrinsic, fee_budget).await?;
+    submit_tx(url, extrinsic, dry_run).await
+}
+
+async fn check_budget(
+    url: &str,
+    extrinsic: &node::extrinsics::Extrinsic<AddAttribute>,
+    fee_budget: Option<u128>,
+) -> Result<(), NodeError> {
+    let budget = match fee_budget {
+        Some(budget) => budget,
+        None => return Ok(()),
+    };
+
+    let fee = extrinsic.estimate_fee(url).await?.partial_fee;
+    match fee > budget {
+        true => Err(NodeError::FeeExceedsBudget(format!("{} > {}", fee, budget))),
+        false => Ok(()),
+    }
+}
+
+async fn submit_tx(
+    url: &str,
+    extrinsic: node::extrinsics::Extrinsic<AddAttribute>,
+    dry_run: bool,
+) -> Result<String, NodeError> {
+    if dry_run {
+        return match extrinsic.dry_run(url).await? {
+            DryRunOutcome::DispatchError(e) => {
+                let metadata = Metadata::new(url).await?;
+                Err(NodeError::ExtrinsicFailed(DispatchFailure::resolve(
+                    &metadata, e,
+                )))
+            }
+            DryRunOutcome::ValidityError(e) => Err(NodeError::InvalidTransaction(e)),
+            DryRunOutcome::Success => Ok(format!("{:?}", DryRunOutcome::Success)),
+        };
+    }
+
+    // Nonce, era, submission and inclusion all have to agree on one view of
+    // the chain, so the whole transaction stays on one endpoint.
+    Endpoints::pinned(url, anchor_tx(url, extrinsic)).await
+}
+
+async fn anchor_tx(
+    url: &str,
+    extrinsic: node::extrinsics::Extrinsic<AddAttribute>,
+) -> Result<String, NodeError> {
+    // Until the pool accepts the transaction its nonce is not spent, so a
+    // failure up to there hands it back. Once accepted it stays reserved
+    // until its era is over, as it may still be included until then.
+    let owner = extrinsic.owner();
+    let release = |e: NodeError| {
+        NonceManager::reset(url, &owner);
+        e
+    };
+
+    let signed = extrinsic.build_signed(url).await?;
+    match Transport::from_url(url) {
+        Transport::Ws => {
+            let mut watch = match signed.submit_and_watch(url).await {
+                Ok(Watched::Watching(watch)) => watch,
+                Ok(Watched::Included(inclusion)) => {
+                    return finalize(url, &signed, inclusion.block_hash).await
+                }
+                Err(e) => return Err(release(e)),
+            };
+            let status = match watch.wait_for(consts::ANCHOR_STAGE).await {
+                Ok(status) => status,
+                Err(e @ NodeError::ExtrinsicNotIncluded(_)) => return Err(release(e)),
+                Err(e) => return Err(not_included(url, &owner, &signed, e).await),
+            };
+            let block_hash = match status.block_hash() {
+                Some(block_hash) => block_hash,
+                None => return Ok(format!("{:?}", status)),
+            };
+
+            match finalize(url, &signed, block_hash).await {
+                Err(e @ NodeError::BlockNotFinalized(_)) => {
+                    Err(not_included(url, &owner, &signed, e).await)
+                }
+                result => result,
+            }
+        }
+        Transport::Http => {
+            if let Err(e) = signed.submit(url).await {
+                return Err(release(e));
+            }
+            let inclusion = match Finality::wait_for_extrinsic(
+                url,
+                signed.hash,
+                signed.signed_at,
+                consts::FINALITY_TIMEOUT,
+            )
+            .await
+            {
+                Ok(inclusion) => inclusion,
+                Err(e) => return Err(not_included(url, &owner, &signed, e).await),
+            };
+            check_outcome(url, inclusion.block_hash, &signed.hex()).await?;
+            Ok(format!(
+                "{:?} Finalized({:?})",
+                signed.hash, inclusion.block_hash
+            ))
+        }
+    }
+}
+
+/// An accepted transaction that never makes it into a block leaves a gap at
+/// its nonce. Once its era is over nothing can fill it, so unless the chain
+/// got past it anyway the local nonce syncs with the chain again.
+async fn not_included(
+    url: &str,
+    owner: &AccountId32,
+    signed: &SignedExtrinsic,
+    error: NodeError,
+) -> NodeError {
+    if let Ok(true) = signed.wait_for_expiry(url).await {
+        if let Err(e) = NonceManager::release_unused(url, owner, signed.nonce).await {
+            println!("↳  Nonce not resynced: {}", report(&e));
+        }
+    }
+    error
+}
+
+async fn finalize(
+    url: &str,
+    signed: &SignedExtrinsic,
+    block_hash: H256,
+) -> Result<String, NodeError> {
+    check_outcome(url, block_hash, &signed.hex()).await?;
+    Finality::wait_for_block(url, block_hash, consts::FINALITY_TIMEOUT).await?;
+    Ok(format!("{:?} Finalized({:?})", signed.hash, block_hash))
+}
+
+async fn check_outcome(url: &str, block_hash: H256, extrinsic: &str) -> Result<(), NodeError> {
+    let outcome = ExtrinsicEvents::find(url, block_hash, extrinsic).await?;
+    match outcome.outcome {
+        ExtrinsicOutcome::Failed(e) => Err(NodeError::ExtrinsicFailed(e)),
+        ExtrinsicOutcome::Success => Ok(()),
+    }
 }
@@ -0,0 +1,144 @@
+use codec::{Compact, Decode};
+use sp_core::crypto::AccountId32;
+use sp_core::H256;
+
+use crate::calls::{block, call::Call};
+use crate::errors::NodeError;
+use crate::extensions;
+use crate::metadata::Metadata;
+use crate::value::{decode_type, Value};
+
+pub use crate::calls::block::BlockResult as Block;
+pub use crate::calls::header::HeaderResult as Header;
+
+pub mod consts {
+    pub const EXTRINSIC_VERSION: u8 = 4;
+    pub const SIGNED: u8 = 0b1000_0000;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedExtrinsic {
+    pub signer: Option<AccountId32>,
+    pub nonce: Option<u32>,
+    pub pallet: String,
+    pub method: String,
+    pub args: Vec<(Option<String>, Value)>,
+}
+
+impl DecodedExtrinsic {
+    pub fn decode(metadata: &Metadata, bytes: &[u8]) -> Result<DecodedExtrinsic, NodeError> {
+        match decode_extrinsic(metadata, bytes) {
+            Ok(extrinsic) => Ok(extrinsic),
+            Err(e) => Err(NodeError::CouldNotDecodeExtrinsic(e.into())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedBlock {
+    pub hash: H256,
+    pub header: Header,
+    pub extrinsics: Vec<DecodedExtrinsic>,
+}
+
+impl DecodedBlock {
+    /// Decodes with the metadata of the runtime that produced the block.
+    pub async fn at(url: &str, block_hash: H256) -> Result<DecodedBlock, NodeError> {
+        let block = block::Block
+            .get(url, Some(vec![format!("{:?}", block_hash)]))
+            .await?;
+        let metadata = Metadata::at_block(url, block_hash).await?;
+        DecodedBlock::new(&metadata, block_hash, block)
+    }
+
+    pub fn new(metadata: &Metadata, hash: H256, block: Block) -> Result<DecodedBlock, NodeError> {
+        let mut extrinsics = Vec::new();
+        for bytes in block.extrinsic_bytes()? {
+            extrinsics.push(DecodedExtrinsic::decode(metadata, &bytes)?);
+        }
+
+        Ok(DecodedBlock {
+            hash,
+            header: block.block.header,
+            extrinsics,
+        })
+    }
+}
+
+/// An extrinsic is a length prefix, a version byte, for signed extrinsics
+/// the address, signature and each signed extension's extra data, then the
+/// call.
+fn decode_extrinsic(metadata: &Metadata, bytes: &[u8]) -> Result<DecodedExtrinsic, codec::Error> {
+    let registry = &metadata.get().types;
+    let types = metadata.extrinsic_types();
+    let input = &mut &bytes[..];
+    Compact::<u32>::decode(input)?;
+
+    let version = u8::decode(input)?;
+    if version & !consts::SIGNED != consts::EXTRINSIC_VERSION {
+        return Err("Unsupported extrinsic version".into());
+    }
+
+    let mut signer = None;
+    let mut nonce = None;
+    if version & consts::SIGNED != 0 {
+        let address = decode_type(registry, types.address, input)?;
+        signer = account(&address);
+        decode_type(registry, types.signature, input)?;
+
+        for extension in &metadata.get().extrinsic.signed_extensions {
+            let extra = decode_type(registry, extension.ty.id, input)?;
+            if extension.identifier == extensions::consts::CHECK_NONCE {
+                nonce = unsigned(&extra).map(|nonce| nonce as u32);
+            }
+        }
+    }
+
+    match decode_type(registry, types.call, input)? {
+        Value::Variant {
+            name: pallet,
+            fields,
+            ..
+        } => match fields.into_iter().next() {
+            Some((
+                _,
+                Value::Variant {
+                    name: method,
+                    fields: args,
+                    ..
+                },
+            )) => Ok(DecodedExtrinsic {
+                signer,
+                nonce,
+                pallet,
+                method,
+                args,
+            }),
+            _ => Err("Call is not a pallet call".into()),
+        },
+        _ => Err("Call is not an enum".into()),
+    }
+}
+
+/// The account behind an address, either a plain account id or the `Id`
+/// variant of a `MultiAddress`.
+fn account(value: &Value) -> Option<AccountId32> {
+    match value {
+        Value::Bytes(bytes) => <[u8; 32]>::try_from(bytes.as_slice())
+            .ok()
+            .map(AccountId32::from),
+        Value::Composite(fields) if fields.len() == 1 => account(&fields[0].1),
+        Value::Variant { name, fields, .. } if name == "Id" && fields.len() == 1 => {
+            account(&fields[0].1)
+        }
+        _ => None,
+    }
+}
+
+fn unsigned(value: &Value) -> Option<u128> {
+    match value {
+        Value::Unsigned(value) => Some(*value),
+        Value::Composite(fields) if fields.len() == 1 => unsigned(&fields[0].1),
+        _ => None,
+    }
+}
@@ -0,0 +1,107 @@
+use crate::calls::call::Call;
+use crate::calls::envelope::RpcError;
+use crate::calls::http::Http;
+use crate::calls::transport::Transport;
+use crate::calls::ws::Ws;
+use crate::endpoints::Endpoints;
+use crate::errors::NodeError;
+use crate::retry::RetryPolicy;
+use serde_json::Value;
+
+pub mod consts {
+    pub const METHOD: &str = "batch";
+    pub const MAX_SIZE: usize = 32;
+}
+
+/// Several calls sent to the node as one JSON-RPC batch array. Each call gets
+/// its own request id, which is how responses find their way back to the
+/// call's decoder whatever order the node answers in. The batch is retried
+/// as a whole, so it follows the policy of the call that retries least.
+#[derive(Default)]
+pub struct Batch {
+    requests: Vec<Http>,
+    policy: RetryPolicy,
+}
+
+pub struct BatchResponse(Vec<String>);
+
+impl Batch {
+    pub fn new() -> Self {
+        Batch::default()
+    }
+
+    /// Adds a call and returns the slot its response will be decoded from.
+    pub fn push<C: Call>(&mut self, call: &C, params: Option<Vec<String>>) -> usize {
+        let slot = self.requests.len();
+        self.requests
+            .push(call.request(params).with_id(slot as u32 + 1));
+        self.policy = self.policy.strictest(call.retry_policy());
+        slot
+    }
+
+    pub async fn send(&self, url: &str) -> Result<BatchResponse, NodeError> {
+        self.policy
+            .run(|| Endpoints::route(url, |endpoint| async move { self.send_to(&endpoint).await }))
+            .await
+    }
+
+    /// Public nodes limit how many requests one batch may hold, so a large
+    /// batch goes out as several smaller ones.
+    async fn send_to(&self, url: &str) -> Result<BatchResponse, NodeError> {
+        let mut bodies = vec![String::new(); self.requests.len()];
+        for chunk in self.requests.chunks(consts::MAX_SIZE) {
+            let responses = match Transport::from_url(url) {
+                Transport::Ws => {
+                    let ws = Ws::connection(url).await?;
+                    ws.send_batch(chunk).await?
+                }
+                Transport::Http => Batch::post(url, chunk).await?,
+            };
+
+            for response in responses {
+                let slot = match serde_json::from_str::<Value>(&response) {
+                    Ok(response) => response["id"].as_u64(),
+                    Err(_) => None,
+                };
+                match slot {
+                    Some(id) if id >= 1 && id as usize <= bodies.len() => {
+                        bodies[id as usize - 1] = response
+                    }
+                    _ => continue,
+                }
+            }
+        }
+        Ok(BatchResponse(bodies))
+    }
+
+    /// A node that rejects the batch as a whole answers with a single error
+    /// object instead of an array.
+    async fn post(url: &str, requests: &[Http]) -> Result<Vec<String>, NodeError> {
+        let response = match reqwest::Client::new().post(url).json(requests).send().await {
+            Ok(response) => response,
+            Err(e) => return Err(NodeError::CouldNotSendHttpsRequest(e.into())),
+        };
+        let body = Http::body(response).await?;
+
+        match serde_json::from_str::<Value>(&body) {
+            Ok(Value::Array(responses)) => Ok(responses.iter().map(Value::to_string).collect()),
+            Ok(response) => match serde_json::from_value::<RpcError>(response["error"].clone()) {
+                Ok(e) => Err(e.into_node_error(consts::METHOD)),
+                Err(e) => Err(NodeError::CouldNotDecodeBatch(e.into())),
+            },
+            Err(e) => Err(NodeError::CouldNotDecodeBatch(e.into())),
+        }
+    }
+}
+
+impl BatchResponse {
+    pub fn decode<C: Call>(&self, call: &C, slot: usize) -> Result<C::ResultType, NodeError> {
+        match self.0.get(slot) {
+            Some(body) if !body.is_empty() => call.decode(body),
+            _ => {
+                let error = format!("No response for {} in batch", C::METHOD);
+                Err(NodeError::CouldNotDecodeBatch(error.into()))
+            }
+        }
+    }
+}
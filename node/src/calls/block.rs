@@ -1,44 +1,55 @@
 use crate::calls::call::Call;
+use crate::calls::header::HeaderResult;
 use crate::errors::NodeError;
 use serde::Deserialize;
+use sp_core::hashing::blake2_256;
+use sp_core::H256;
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-pub struct BlockSuccess {
-    jsonrpc: String,
-    result: BlockResult,
-    id: u8,
-}
-
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct BlockResult {
-    block: BlockData,
-    justifications: Option<String>,
+    pub block: BlockData,
+    pub justifications: Option<serde_json::Value>,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct BlockData {
-    extrinsics: Vec<String>,
-    header: BlockHeader,
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockData {
+    pub header: HeaderResult,
+    pub extrinsics: Vec<String>,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-#[serde(rename_all = "camelCase")]
-struct BlockHeader {
-    digest: BlockHeaderDigest,
-    extrinsics_root: String,
-    number: String,
-    parent_hash: String,
-    state_root: String,
-}
+impl BlockResult {
+    pub fn header(&self) -> &HeaderResult {
+        &self.block.header
+    }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-struct BlockHeaderDigest {
-    logs: Vec<String>,
+    pub fn number(&self) -> u64 {
+        self.block.header.number
+    }
+
+    /// Extrinsics hex encoded, as the node returns them.
+    pub fn extrinsics(&self) -> &[String] {
+        &self.block.extrinsics
+    }
+
+    pub fn extrinsic_bytes(&self) -> Result<Vec<Vec<u8>>, NodeError> {
+        let mut extrinsics = Vec::new();
+        for extrinsic in &self.block.extrinsics {
+            match hex::decode(extrinsic.trim_start_matches("0x")) {
+                Ok(bytes) => extrinsics.push(bytes),
+                Err(e) => return Err(NodeError::CouldNotDecodeExtrinsic(e.into())),
+            }
+        }
+        Ok(extrinsics)
+    }
+
+    /// Extrinsic hashes in block order, as `author_submitExtrinsic` returns them.
+    pub fn extrinsic_hashes(&self) -> Result<Vec<H256>, NodeError> {
+        Ok(self
+            .extrinsic_bytes()?
+            .iter()
+            .map(|bytes| H256(blake2_256(bytes)))
+            .collect())
+    }
 }
 
 pub struct Block;
@@ -47,15 +58,7 @@ impl Call for Block {
     type ResultType = BlockResult;
     const METHOD: &'static str = "chain_getBlock";
 
-    async fn get(
-        &self,
-        url: &str,
-        params: Option<Vec<String>>,
-    ) -> Result<Self::ResultType, NodeError> {
-        let body = self.body(url, params).await?;
-        match serde_json::from_str::<BlockSuccess>(&body) {
-            Ok(nonce) => Ok(nonce.result),
-            Err(e) => Err(NodeError::CouldNotGetAccountNonce(e.to_string())),
-        }
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        self.result(body, NodeError::CouldNotGetBlock)
     }
 }
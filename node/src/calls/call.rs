@@ -1,5 +1,11 @@
+use crate::calls::envelope::Envelope;
 use crate::calls::http::Http;
-use crate::errors::NodeError;
+use crate::calls::transport::Transport;
+use crate::calls::ws::Ws;
+use crate::endpoints::Endpoints;
+use crate::errors::{NodeError, Source};
+use crate::retry::RetryPolicy;
+use serde::de::DeserializeOwned;
 use std::future::Future;
 
 pub trait Call: Sized {
@@ -10,6 +16,14 @@ pub trait Call: Sized {
         Http::new(Self::METHOD, params)
     }
 
+    fn transport(&self, url: &str) -> Transport {
+        Transport::from_url(url)
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
     fn response(
         &self,
         url: &str,
@@ -22,7 +36,7 @@ pub trait Call: Sized {
             let http = self.request(params);
             match http.send(url).await {
                 Ok(result) => Ok(result),
-                Err(e) => Err(NodeError::CouldNotSendHttpsRequest(e.to_string())),
+                Err(e) => Err(NodeError::CouldNotSendHttpsRequest(e.into())),
             }
         }
     }
@@ -36,17 +50,44 @@ pub trait Call: Sized {
         Self: Sync,
     {
         async {
-            let response = self.response(url, params).await?;
-            match response.text().await {
-                Ok(body) => Ok(body),
-                Err(e) => Err(NodeError::CouldNotSendHttpsRequest(e.to_string())),
+            if let Transport::Ws = self.transport(url) {
+                let ws = Ws::connection(url).await?;
+                return ws.send(&self.request(params)).await;
             }
+
+            let response = self.response(url, params).await?;
+            Http::body(response).await
         }
     }
 
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError>;
+
+    fn result<T: DeserializeOwned>(
+        &self,
+        body: &str,
+        error: fn(Source) -> NodeError,
+    ) -> Result<T, NodeError> {
+        Envelope::result(body, Self::METHOD, error)
+    }
+
     fn get(
         &self,
         url: &str,
         params: Option<Vec<String>>,
-    ) -> impl Future<Output = Result<Self::ResultType, NodeError>> + Send;
+    ) -> impl Future<Output = Result<Self::ResultType, NodeError>> + Send
+    where
+        Self: Sync,
+    {
+        async move {
+            let params = &params;
+            self.retry_policy()
+                .run(|| {
+                    Endpoints::route(url, move |endpoint| async move {
+                        let body = self.body(&endpoint, params.clone()).await?;
+                        self.decode(&body)
+                    })
+                })
+                .await
+        }
+    }
 }
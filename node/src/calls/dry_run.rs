@@ -0,0 +1,66 @@
+use crate::calls::call::Call;
+use crate::calls::state_call::StateCall;
+use crate::errors::NodeError;
+use codec::Decode;
+use sp_runtime::transaction_validity::TransactionValidityError;
+use sp_runtime::{ApplyExtrinsicResult, DispatchError};
+
+pub mod consts {
+    pub const APPLY_EXTRINSIC: &str = "BlockBuilder_apply_extrinsic";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DryRunOutcome {
+    Success,
+    DispatchError(DispatchError),
+    ValidityError(TransactionValidityError),
+}
+
+impl From<ApplyExtrinsicResult> for DryRunOutcome {
+    fn from(result: ApplyExtrinsicResult) -> Self {
+        match result {
+            Ok(Ok(())) => DryRunOutcome::Success,
+            Ok(Err(e)) => DryRunOutcome::DispatchError(e),
+            Err(e) => DryRunOutcome::ValidityError(e),
+        }
+    }
+}
+
+fn decode_outcome(bytes: &[u8]) -> Result<DryRunOutcome, NodeError> {
+    match ApplyExtrinsicResult::decode(&mut &bytes[..]) {
+        Ok(result) => Ok(result.into()),
+        Err(e) => Err(NodeError::CouldNotDryRun(e.into())),
+    }
+}
+
+/// Applies a signed extrinsic on top of the best block without broadcasting
+/// it. `system_dryRun` is an unsafe RPC that public nodes usually refuse, so
+/// the block builder runtime API is used as a fallback.
+pub struct DryRun;
+
+impl DryRun {
+    pub async fn run(url: &str, extrinsic: &[u8]) -> Result<DryRunOutcome, NodeError> {
+        let params = Some(vec![format!("0x{}", hex::encode(extrinsic))]);
+        if let Ok(outcome) = DryRun.get(url, params).await {
+            return Ok(outcome);
+        }
+
+        let params = StateCall::params(consts::APPLY_EXTRINSIC, extrinsic);
+        let bytes = StateCall.get(url, params).await?;
+        decode_outcome(&bytes)
+    }
+}
+
+impl Call for DryRun {
+    type ResultType = DryRunOutcome;
+    const METHOD: &'static str = "system_dryRun";
+
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        let res: String = self.result(body, NodeError::CouldNotDryRun)?;
+
+        match hex::decode(res.trim_start_matches("0x")) {
+            Ok(bytes) => decode_outcome(&bytes),
+            Err(e) => Err(NodeError::CouldNotDryRun(e.into())),
+        }
+    }
+}
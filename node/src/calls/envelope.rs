@@ -0,0 +1,66 @@
+use crate::errors::{NodeError, Source};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn into_node_error(self, method: &str) -> NodeError {
+        let data = match self.data {
+            Some(Value::String(data)) => Some(data),
+            Some(Value::Null) | None => None,
+            Some(data) => Some(data.to_string()),
+        };
+
+        NodeError::Rpc {
+            code: self.code,
+            message: self.message,
+            data,
+            method: method.to_string(),
+        }
+    }
+}
+
+/// The JSON-RPC envelope every response shares. A node answers with either
+/// a `result` or an `error` object, never both.
+#[allow(dead_code)]
+#[derive(Debug, Deserialize)]
+pub struct Envelope {
+    jsonrpc: String,
+    #[serde(default)]
+    result: Value,
+    error: Option<RpcError>,
+    id: Value,
+}
+
+impl Envelope {
+    /// Turns an `error` object into `NodeError::Rpc` and deserializes the
+    /// `result` otherwise. Malformed bodies and results are reported with
+    /// `error`, the caller's own variant.
+    pub fn result<T: DeserializeOwned>(
+        body: &str,
+        method: &str,
+        error: fn(Source) -> NodeError,
+    ) -> Result<T, NodeError> {
+        let envelope = match serde_json::from_str::<Envelope>(body) {
+            Ok(envelope) => envelope,
+            Err(e) => return Err(error(e.into())),
+        };
+
+        if let Some(e) = envelope.error {
+            return Err(e.into_node_error(method));
+        }
+
+        match serde_json::from_value::<T>(envelope.result) {
+            Ok(result) => Ok(result),
+            Err(e) => Err(error(e.into())),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use crate::calls::call::Call;
+use crate::errors::NodeError;
+use sp_core::H256;
+
+pub struct FinalizedHead;
+
+impl Call for FinalizedHead {
+    type ResultType = H256;
+    const METHOD: &'static str = "chain_getFinalizedHead";
+
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        self.result(body, NodeError::CouldNotGetHeader)
+    }
+}
@@ -0,0 +1,65 @@
+use crate::calls::call::Call;
+use crate::errors::NodeError;
+use codec::Decode;
+use serde::{Deserialize, Deserializer};
+use sp_core::H256;
+use sp_runtime::{ConsensusEngineId, DigestItem};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeaderResult {
+    pub parent_hash: H256,
+    #[serde(deserialize_with = "hex_number")]
+    pub number: u64,
+    pub state_root: H256,
+    pub extrinsics_root: H256,
+    pub digest: HeaderDigest,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeaderDigest {
+    #[serde(deserialize_with = "digest_logs")]
+    pub logs: Vec<DigestItem>,
+}
+
+impl HeaderDigest {
+    /// The block author's pre-runtime digest, e.g. the Aura slot.
+    pub fn pre_runtime(&self) -> Option<(ConsensusEngineId, &[u8])> {
+        self.logs.iter().find_map(|log| log.as_pre_runtime())
+    }
+
+    pub fn seal(&self) -> Option<(ConsensusEngineId, &[u8])> {
+        self.logs.iter().find_map(|log| log.as_seal())
+    }
+}
+
+fn hex_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let number = String::deserialize(deserializer)?;
+    u64::from_str_radix(number.trim_start_matches("0x"), 16).map_err(serde::de::Error::custom)
+}
+
+/// A log that does not decode, say from a digest kind this crate does not
+/// know, is kept as `DigestItem::Other` with its raw bytes rather than
+/// failing the whole header. A log that isn't hex is a malformed response.
+fn digest_logs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<DigestItem>, D::Error> {
+    let mut logs = Vec::new();
+    for log in Vec::<String>::deserialize(deserializer)? {
+        let bytes = hex::decode(log.trim_start_matches("0x")).map_err(serde::de::Error::custom)?;
+        match DigestItem::decode(&mut bytes.as_slice()) {
+            Ok(log) => logs.push(log),
+            Err(_) => logs.push(DigestItem::Other(bytes)),
+        }
+    }
+    Ok(logs)
+}
+
+pub struct Header;
+
+impl Call for Header {
+    type ResultType = HeaderResult;
+    const METHOD: &'static str = "chain_getHeader";
+
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        self.result(body, NodeError::CouldNotGetHeader)
+    }
+}
@@ -1,21 +1,27 @@
 use crate::errors::NodeError;
-use reqwest::Response;
+use reqwest::{Response, StatusCode};
 use serde::Serialize;
+use serde_json::Value;
 
 #[derive(Debug, Serialize)]
 pub struct Http {
-    id: u8,
+    id: u32,
     jsonrpc: String,
     method: String,
-    params: Vec<String>,
+    params: Vec<Value>,
 }
 
 impl Http {
     pub fn new(method: &str, params: Option<Vec<String>>) -> Self {
         let params = match params {
-            Some(p) => p,
+            Some(p) => p.into_iter().map(Value::String).collect(),
             None => Vec::new(),
         };
+        Http::raw(method, params)
+    }
+
+    /// For methods that take numbers or objects rather than strings.
+    pub fn raw(method: &str, params: Vec<Value>) -> Self {
         Http {
             id: 1,
             jsonrpc: "2.0".to_string(),
@@ -24,14 +30,40 @@ impl Http {
         }
     }
 
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Reads a response body, turning rate limiting and server errors into
+    /// errors that are retried and count against the endpoint.
+    pub async fn body(response: Response) -> Result<String, NodeError> {
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(NodeError::RateLimited(status.to_string()));
+        }
+        if status.is_server_error() {
+            return Err(NodeError::CouldNotSendHttpsRequest(
+                status.to_string().into(),
+            ));
+        }
+
+        match response.text().await {
+            Ok(body) => Ok(body),
+            Err(e) => Err(NodeError::CouldNotSendHttpsRequest(e.into())),
+        }
+    }
+
     pub async fn send(&self, url: &str) -> Result<Response, NodeError> {
         let response = reqwest::Client::new().post(url).json(&self).send().await;
 
         match response {
             Ok(result) => Ok(result),
-            Err(e) => Err(NodeError::CouldNotSendHttpsRequest(e.to_string())),
+            Err(e) => Err(NodeError::CouldNotSendHttpsRequest(e.into())),
         }
     }
 }
-
-trait Call {}
@@ -0,0 +1,65 @@
+use crate::calls::call::Call;
+use crate::calls::http::Http;
+use crate::errors::NodeError;
+use serde_json::Value;
+use sp_core::H256;
+
+/// One page of storage keys under `prefix`. The page size has to go out as
+/// a JSON number, so the call carries its own params rather than strings.
+pub struct KeysPaged {
+    prefix: Vec<u8>,
+    count: u32,
+    start_key: Option<Vec<u8>>,
+    at: Option<H256>,
+}
+
+impl KeysPaged {
+    pub fn new(prefix: &[u8], count: u32, start_key: Option<&[u8]>) -> Self {
+        KeysPaged {
+            prefix: prefix.to_vec(),
+            count,
+            start_key: start_key.map(<[u8]>::to_vec),
+            at: None,
+        }
+    }
+
+    /// Pages the keys as of `block_hash` instead of the best block.
+    pub fn at(mut self, block_hash: Option<H256>) -> Self {
+        self.at = block_hash;
+        self
+    }
+}
+
+impl Call for KeysPaged {
+    type ResultType = Vec<Vec<u8>>;
+    const METHOD: &'static str = "state_getKeysPaged";
+
+    fn request(&self, _params: Option<Vec<String>>) -> Http {
+        let mut params = vec![
+            Value::String(format!("0x{}", hex::encode(&self.prefix))),
+            Value::from(self.count),
+        ];
+        let start_key = match &self.start_key {
+            Some(start_key) => Value::String(format!("0x{}", hex::encode(start_key))),
+            None => Value::Null,
+        };
+        params.push(start_key);
+        if let Some(at) = self.at {
+            params.push(Value::String(format!("{:?}", at)));
+        }
+        Http::raw(Self::METHOD, params)
+    }
+
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        let keys: Vec<String> = self.result(body, NodeError::CouldNotGetStorageKeys)?;
+
+        let mut decoded = Vec::new();
+        for key in keys {
+            match hex::decode(key.trim_start_matches("0x")) {
+                Ok(key) => decoded.push(key),
+                Err(e) => return Err(NodeError::CouldNotGetStorageKeys(e.into())),
+            }
+        }
+        Ok(decoded)
+    }
+}
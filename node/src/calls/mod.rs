@@ -1,9 +1,22 @@
+pub mod batch;
 pub mod block;
 pub mod block_hash;
 pub mod call;
+pub mod dry_run;
+pub mod envelope;
 pub mod extrinsic;
+pub mod finalized_head;
+pub mod header;
 pub mod http;
+pub mod keys_paged;
 pub mod nonce;
+pub mod query_info;
 pub mod runtime_metadata;
 pub mod runtime_version;
+pub mod state_call;
 pub mod storage;
+pub mod submit_and_watch;
+pub mod subscribe_finalized_heads;
+pub mod subscription;
+pub mod transport;
+pub mod ws;
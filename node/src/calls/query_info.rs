@@ -0,0 +1,89 @@
+use crate::calls::call::Call;
+use crate::calls::state_call::StateCall;
+use crate::errors::NodeError;
+use codec::{Decode, Encode};
+use serde::{Deserialize, Deserializer};
+
+pub mod consts {
+    pub const QUERY_INFO: &str = "TransactionPaymentApi_query_info";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Weight {
+    #[codec(compact)]
+    pub ref_time: u64,
+    #[codec(compact)]
+    pub proof_size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DispatchClass {
+    Normal,
+    Operational,
+    Mandatory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DispatchInfo {
+    pub weight: Weight,
+    pub class: DispatchClass,
+    #[serde(deserialize_with = "balance")]
+    pub partial_fee: u128,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(u64),
+    String(String),
+}
+
+/// Nodes return the fee as a JSON number, a decimal string or, through
+/// `NumberOrHex`, a `0x` hex string.
+fn balance<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    let balance = match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(balance) => return Ok(balance.into()),
+        NumberOrString::String(balance) => balance,
+    };
+
+    match balance.strip_prefix("0x") {
+        Some(hex) => u128::from_str_radix(hex, 16).map_err(serde::de::Error::custom),
+        None => balance.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+pub struct QueryInfo;
+
+impl QueryInfo {
+    /// Prefers the `TransactionPaymentApi` runtime API and falls back to the
+    /// `payment_queryInfo` RPC on nodes that don't expose `state_call` for it.
+    pub async fn estimate(url: &str, extrinsic: &[u8]) -> Result<DispatchInfo, NodeError> {
+        let mut data = extrinsic.to_vec();
+        (extrinsic.len() as u32).encode_to(&mut data);
+
+        if let Ok(bytes) = StateCall
+            .get(url, StateCall::params(consts::QUERY_INFO, &data))
+            .await
+        {
+            return match DispatchInfo::decode(&mut bytes.as_slice()) {
+                Ok(info) => Ok(info),
+                Err(e) => Err(NodeError::CouldNotQueryFee(e.into())),
+            };
+        }
+
+        let extrinsic = format!("0x{}", hex::encode(extrinsic));
+        QueryInfo.get(url, Some(vec![extrinsic])).await
+    }
+}
+
+impl Call for QueryInfo {
+    type ResultType = DispatchInfo;
+    const METHOD: &'static str = "payment_queryInfo";
+
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        self.result(body, NodeError::CouldNotQueryFee)
+    }
+}
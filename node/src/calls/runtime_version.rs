@@ -23,29 +23,13 @@ pub struct RuntimeData {
     pub transaction_version: u32,
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Deserialize)]
-pub struct RuntimeVersionSuccess {
-    jsonrpc: String,
-    result: RuntimeData,
-    id: u8,
-}
-
 pub struct RuntimeVersion;
 
 impl Call for RuntimeVersion {
     type ResultType = RuntimeData;
     const METHOD: &'static str = "state_getRuntimeVersion";
 
-    async fn get(
-        &self,
-        url: &str,
-        params: Option<Vec<String>>,
-    ) -> Result<Self::ResultType, NodeError> {
-        let body = self.body(url, params).await?;
-        match serde_json::from_str::<RuntimeVersionSuccess>(&body) {
-            Ok(nonce) => Ok(nonce.result),
-            Err(e) => Err(NodeError::CouldNotGetAccountNonce(e.to_string())),
-        }
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        self.result(body, NodeError::CouldNotGetRuntimeVersion)
     }
 }
@@ -0,0 +1,24 @@
+use crate::calls::call::Call;
+use crate::errors::NodeError;
+
+pub struct StateCall;
+
+impl StateCall {
+    pub fn params(method: &str, data: &[u8]) -> Option<Vec<String>> {
+        Some(vec![method.to_string(), format!("0x{}", hex::encode(data))])
+    }
+}
+
+impl Call for StateCall {
+    type ResultType = Vec<u8>;
+    const METHOD: &'static str = "state_call";
+
+    fn decode(&self, body: &str) -> Result<Self::ResultType, NodeError> {
+        let res: String = self.result(body, NodeError::CouldNotCallRuntimeApi)?;
+
+        match hex::decode(res.trim_start_matches("0x")) {
+            Ok(bytes) => Ok(bytes),
+            Err(e) => Err(NodeError::CouldNotCallRuntimeApi(e.into())),
+        }
+    }
+}
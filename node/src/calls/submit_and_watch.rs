@@ -0,0 +1,119 @@
+use crate::calls::call::Call;
+use crate::calls::subscription::TypedSubscription;
+use crate::errors::NodeError;
+use serde::Deserialize;
+use sp_core::H256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExtrinsicStatus {
+    Future,
+    Ready,
+    Broadcast(Vec<String>),
+    InBlock(H256),
+    Retracted(H256),
+    FinalityTimeout(H256),
+    Finalized(H256),
+    Usurped(H256),
+    Dropped,
+    Invalid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExtrinsicStage {
+    Ready,
+    Broadcast,
+    InBlock,
+    Finalized,
+}
+
+impl ExtrinsicStatus {
+    pub fn stage(&self) -> Option<ExtrinsicStage> {
+        match self {
+            ExtrinsicStatus::Ready => Some(ExtrinsicStage::Ready),
+            ExtrinsicStatus::Broadcast(_) => Some(ExtrinsicStage::Broadcast),
+            ExtrinsicStatus::InBlock(_) => Some(ExtrinsicStage::InBlock),
+            ExtrinsicStatus::Finalized(_) => Some(ExtrinsicStage::Finalized),
+            _ => None,
+        }
+    }
+
+    pub fn block_hash(&self) -> Option<H256> {
+        match self {
+            ExtrinsicStatus::InBlock(hash)
+            | ExtrinsicStatus::Retracted(hash)
+            | ExtrinsicStatus::FinalityTimeout(hash)
+            | ExtrinsicStatus::Finalized(hash) => Some(*hash),
+            _ => None,
+        }
+    }
+
+    /// Statuses after which the node sends no further updates.
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            ExtrinsicStatus::Finalized(_)
+                | ExtrinsicStatus::FinalityTimeout(_)
+                | ExtrinsicStatus::Usurped(_)
+                | ExtrinsicStatus::Dropped
+                | ExtrinsicStatus::Invalid
+        )
+    }
+}
+
+pub type ExtrinsicWatch = TypedSubscription<ExtrinsicStatus>;
+
+impl ExtrinsicWatch {
+    /// Waits until the extrinsic reaches `stage`, failing as soon as the node
+    /// reports that it can no longer get there.
+    pub async fn wait_for(&mut self, stage: ExtrinsicStage) -> Result<ExtrinsicStatus, NodeError> {
+        while let Some(status) = self.next().await {
+            let status = status?;
+            if status.stage().is_some_and(|reached| reached >= stage) {
+                return Ok(status);
+            }
+            if status.is_final() {
+                return Err(NodeError::ExtrinsicNotIncluded(format!("{:?}", status)));
+            }
+        }
+
+        // The pool may still have the extrinsic, only the watch is gone
+        Err(NodeError::CouldNotReadMessageFromNode(
+            "Subscription closed".into(),
+        ))
+    }
+}
+
+pub struct SubmitAndWatch;
+
+impl SubmitAndWatch {
+    const UNSUBSCRIBE: &'static str = "author_unwatchExtrinsic";
+}
+
+impl Call for SubmitAndWatch {
+    type ResultType = ExtrinsicWatch;
+    const METHOD: &'static str = "author_submitAndWatchExtrinsic";
+
+    fn decode(&self, _body: &str) -> Result<Self::ResultType, NodeError> {
+        Err(NodeError::SubscriptionRequiresWebSocket)
+    }
+
+    async fn get(
+        &self,
+        url: &str,
+        params: Option<Vec<String>>,
+    ) -> Result<Self::ResultType, NodeError> {
+        let watch = TypedSubscription::new(
+            self,
+            url,
+            params,
+            Self::UNSUBSCRIBE,
+            NodeError::CouldNotCallExtrinsic,
+        );
+        match watch.await {
+            Ok(watch) => Ok(watch),
+            Err(NodeError::CouldNotSubscribe(e)) => Err(NodeError::CouldNotCallExtrinsic(e)),
+            Err(e) => Err(e),
+        }
+    }
+}
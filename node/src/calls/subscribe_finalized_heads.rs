@@ -0,0 +1,36 @@
+use crate::calls::call::Call;
+use crate::calls::header::HeaderResult;
+use crate::calls::subscription::TypedSubscription;
+use crate::errors::NodeError;
+
+pub type FinalizedHeads = TypedSubscription<HeaderResult>;
+
+pub struct SubscribeFinalizedHeads;
+
+impl SubscribeFinalizedHeads {
+    const UNSUBSCRIBE: &'static str = "chain_unsubscribeFinalizedHeads";
+}
+
+impl Call for SubscribeFinalizedHeads {
+    type ResultType = FinalizedHeads;
+    const METHOD: &'static str = "chain_subscribeFinalizedHeads";
+
+    fn decode(&self, _body: &str) -> Result<Self::ResultType, NodeError> {
+        Err(NodeError::SubscriptionRequiresWebSocket)
+    }
+
+    async fn get(
+        &self,
+        url: &str,
+        params: Option<Vec<String>>,
+    ) -> Result<Self::ResultType, NodeError> {
+        TypedSubscription::new(
+            self,
+            url,
+            params,
+            Self::UNSUBSCRIBE,
+            NodeError::CouldNotGetHeader,
+        )
+        .await
+    }
+}
@@ -0,0 +1,69 @@
+use crate::calls::call::Call;
+use crate::calls::transport::Transport;
+use crate::calls::ws::{Subscription, Ws};
+use crate::endpoints::Endpoints;
+use crate::errors::{NodeError, Source};
+use async_std::stream::Stream;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A subscription whose notifications are deserialized into `T`. The ones
+/// that don't deserialize are reported with `error`, the call's own variant.
+pub struct TypedSubscription<T> {
+    subscription: Subscription,
+    error: fn(Source) -> NodeError,
+    item: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> TypedSubscription<T> {
+    /// Subscribes with `call`, which needs a WebSocket endpoint.
+    pub async fn new<C: Call + Sync>(
+        call: &C,
+        url: &str,
+        params: Option<Vec<String>>,
+        unsubscribe: &'static str,
+        error: fn(Source) -> NodeError,
+    ) -> Result<Self, NodeError> {
+        Endpoints::route(url, |endpoint| async move {
+            if let Transport::Http = call.transport(&endpoint) {
+                return Err(NodeError::SubscriptionRequiresWebSocket);
+            }
+
+            let ws = Ws::connection(&endpoint).await?;
+            let subscription = ws.subscribe(&call.request(params), unsubscribe).await?;
+            Ok(TypedSubscription {
+                subscription,
+                error,
+                item: PhantomData,
+            })
+        })
+        .await
+    }
+
+    pub async fn next(&mut self) -> Option<Result<T, NodeError>> {
+        let item = self.subscription.next().await?;
+        Some(self.decode(item))
+    }
+
+    fn decode(&self, item: Value) -> Result<T, NodeError> {
+        match serde_json::from_value::<T>(item) {
+            Ok(item) => Ok(item),
+            Err(e) => Err((self.error)(e.into())),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Stream for TypedSubscription<T> {
+    type Item = Result<T, NodeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut self.subscription).poll_next(cx) {
+            Poll::Ready(Some(item)) => Poll::Ready(Some(self.decode(item))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
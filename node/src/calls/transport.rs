@@ -0,0 +1,14 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transport {
+    Http,
+    Ws,
+}
+
+impl Transport {
+    pub fn from_url(url: &str) -> Self {
+        match url.starts_with("ws://") || url.starts_with("wss://") {
+            true => Transport::Ws,
+            false => Transport::Http,
+        }
+    }
+}
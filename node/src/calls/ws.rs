@@ -0,0 +1,423 @@
+use crate::calls::batch;
+use crate::calls::envelope::{Envelope, RpcError};
+use crate::calls::http::Http;
+use crate::errors::NodeError;
+use async_std::channel::{self, Receiver as AsyncReceiver, Sender as AsyncSender};
+use async_std::stream::Stream;
+use async_std::task;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::io::ErrorKind;
+use std::net::TcpStream;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+use std::task::{Context, Poll};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{stream::MaybeTlsStream, Message, WebSocket};
+
+pub mod consts {
+    use std::time::Duration;
+
+    pub const READ_TIMEOUT: Duration = Duration::from_millis(20);
+    // How long a request sent to an idle connection may wait to go out
+    pub const IDLE_READ_TIMEOUT: Duration = Duration::from_millis(250);
+    pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+}
+
+type Socket = WebSocket<MaybeTlsStream<TcpStream>>;
+type Reply = AsyncSender<Result<String, NodeError>>;
+type Response = AsyncReceiver<Result<String, NodeError>>;
+type Notify = AsyncSender<Value>;
+
+struct Registry<K, V>(Mutex<HashMap<K, V>>);
+
+impl<K: Eq + Hash, V> Registry<K, V> {
+    fn new() -> Self {
+        Registry(Mutex::new(HashMap::new()))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, V>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct Pending {
+    caller_id: Value,
+    reply: Reply,
+    notify: Option<Notify>,
+    in_batch: bool,
+}
+
+/// One persistent connection per node url. A background thread owns the
+/// socket and routes responses back to their callers by request id. The
+/// pool keeps the connection until it closes, and once the last handle to
+/// it is dropped the thread closes the socket and exits.
+pub struct Ws {
+    next_id: AtomicU64,
+    closed: Arc<AtomicBool>,
+    outgoing: Sender<Message>,
+    pending: Arc<Registry<u64, Pending>>,
+    subscriptions: Arc<Registry<String, Notify>>,
+}
+
+fn pool() -> MutexGuard<'static, HashMap<String, Arc<Ws>>> {
+    static POOL: OnceLock<Mutex<HashMap<String, Arc<Ws>>>> = OnceLock::new();
+    POOL.get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Ws {
+    /// The handshake blocks, so it runs on a blocking thread without the
+    /// pool locked. When two callers connect at once the first one into the
+    /// pool wins, and the other connection closes as soon as it is dropped.
+    pub async fn connection(url: &str) -> Result<Arc<Ws>, NodeError> {
+        if let Some(ws) = Ws::pooled(url) {
+            return Ok(ws);
+        }
+
+        let endpoint = url.to_string();
+        let ws = Arc::new(task::spawn_blocking(move || Ws::connect(&endpoint)).await?);
+
+        let mut pool = pool();
+        match pool.get(url) {
+            Some(pooled) if !pooled.is_closed() => Ok(pooled.clone()),
+            _ => {
+                pool.insert(url.to_string(), ws.clone());
+                Ok(ws)
+            }
+        }
+    }
+
+    fn pooled(url: &str) -> Option<Arc<Ws>> {
+        let mut pool = pool();
+        match pool.get(url) {
+            Some(ws) if !ws.is_closed() => Some(ws.clone()),
+            Some(_) => {
+                pool.remove(url);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn connect(url: &str) -> Result<Self, NodeError> {
+        let socket = match tungstenite::connect(url) {
+            Ok((socket, _)) => socket,
+            Err(e) => return Err(NodeError::CouldNotConnectToNode(e.into())),
+        };
+        if let Err(e) = set_read_timeout(&socket, consts::IDLE_READ_TIMEOUT) {
+            return Err(NodeError::CouldNotConnectToNode(e.into()));
+        }
+
+        let (outgoing, incoming) = mpsc::channel();
+        let pending = Arc::new(Registry::new());
+        let subscriptions = Arc::new(Registry::new());
+        let closed = Arc::new(AtomicBool::new(false));
+
+        let worker = Worker {
+            socket,
+            incoming,
+            pending: pending.clone(),
+            subscriptions: subscriptions.clone(),
+            closed: closed.clone(),
+            idle: true,
+        };
+        thread::spawn(move || worker.run());
+
+        Ok(Ws {
+            next_id: AtomicU64::new(1),
+            closed,
+            outgoing,
+            pending,
+            subscriptions,
+        })
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    pub async fn send(&self, http: &Http) -> Result<String, NodeError> {
+        self.request(http, None).await
+    }
+
+    pub async fn subscribe(
+        self: &Arc<Self>,
+        http: &Http,
+        unsubscribe: &'static str,
+    ) -> Result<Subscription, NodeError> {
+        let (notify, notifications) = channel::unbounded();
+        let body = self.request(http, Some(notify)).await?;
+
+        let result: Value = Envelope::result(&body, http.method(), NodeError::CouldNotSubscribe)?;
+
+        match subscription_id(&result) {
+            Some(id) => Ok(Subscription {
+                id,
+                unsubscribe,
+                notifications,
+                ws: self.clone(),
+            }),
+            None => Err(NodeError::CouldNotSubscribe(result.to_string().into())),
+        }
+    }
+
+    pub async fn send_batch(&self, requests: &[Http]) -> Result<Vec<String>, NodeError> {
+        let mut ids = Vec::new();
+        let mut batch = Vec::new();
+        let mut responses = Vec::new();
+        for http in requests {
+            let (id, request, response) = self.register(http, None, true)?;
+            ids.push(id);
+            batch.push(request);
+            responses.push(response);
+        }
+
+        self.write(&ids, Value::Array(batch))?;
+
+        let mut bodies = Vec::new();
+        for (id, response) in ids.into_iter().zip(responses) {
+            bodies.push(self.reply(id, response).await?);
+        }
+        Ok(bodies)
+    }
+
+    async fn request(&self, http: &Http, notify: Option<Notify>) -> Result<String, NodeError> {
+        let (id, request, response) = self.register(http, notify, false)?;
+        self.write(&[id], request)?;
+        self.reply(id, response).await
+    }
+
+    fn register(
+        &self,
+        http: &Http,
+        notify: Option<Notify>,
+        in_batch: bool,
+    ) -> Result<(u64, Value, Response), NodeError> {
+        let (id, caller_id, request) = self.tag(http)?;
+
+        let (reply, response) = channel::bounded(1);
+        let pending = Pending {
+            caller_id,
+            reply,
+            notify,
+            in_batch,
+        };
+        self.pending.lock().insert(id, pending);
+        Ok((id, request, response))
+    }
+
+    fn write(&self, ids: &[u64], request: Value) -> Result<(), NodeError> {
+        match self.outgoing.send(Message::Text(request.to_string())) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let mut pending = self.pending.lock();
+                for id in ids {
+                    pending.remove(id);
+                }
+                Err(NodeError::CouldNotSendMessageToNode(e.into()))
+            }
+        }
+    }
+
+    async fn reply(&self, id: u64, response: Response) -> Result<String, NodeError> {
+        match async_std::future::timeout(consts::REQUEST_TIMEOUT, response.recv()).await {
+            Ok(Ok(body)) => body,
+            Ok(Err(e)) => Err(NodeError::CouldNotReadMessageFromNode(e.into())),
+            Err(e) => {
+                self.pending.lock().remove(&id);
+                Err(NodeError::CouldNotReadMessageFromNode(e.into()))
+            }
+        }
+    }
+
+    fn tag(&self, http: &Http) -> Result<(u64, Value, Value), NodeError> {
+        let mut request = match serde_json::to_value(http) {
+            Ok(request) => request,
+            Err(e) => return Err(NodeError::CouldNotSendMessageToNode(e.into())),
+        };
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let caller_id = std::mem::replace(&mut request["id"], Value::from(id));
+        Ok((id, caller_id, request))
+    }
+
+    fn unsubscribe(&self, id: &str, method: &str) {
+        self.subscriptions.lock().remove(id);
+        if let Ok((_, _, request)) = self.tag(&Http::new(method, Some(vec![id.to_string()]))) {
+            let _ = self.outgoing.send(Message::Text(request.to_string()));
+        }
+    }
+}
+
+pub struct Subscription {
+    id: String,
+    unsubscribe: &'static str,
+    notifications: AsyncReceiver<Value>,
+    ws: Arc<Ws>,
+}
+
+impl Subscription {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub async fn next(&mut self) -> Option<Value> {
+        self.notifications.recv().await.ok()
+    }
+}
+
+impl Stream for Subscription {
+    type Item = Value;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.notifications).poll_next(cx)
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.ws.unsubscribe(&self.id, self.unsubscribe);
+    }
+}
+
+fn subscription_id(value: &Value) -> Option<String> {
+    match value {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn set_read_timeout(socket: &Socket, timeout: Duration) -> std::io::Result<()> {
+    match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream.set_read_timeout(Some(timeout)),
+        MaybeTlsStream::NativeTls(stream) => stream.get_ref().set_read_timeout(Some(timeout)),
+        _ => Ok(()),
+    }
+}
+
+struct Worker {
+    socket: Socket,
+    incoming: Receiver<Message>,
+    pending: Arc<Registry<u64, Pending>>,
+    subscriptions: Arc<Registry<String, Notify>>,
+    closed: Arc<AtomicBool>,
+    idle: bool,
+}
+
+impl Worker {
+    fn run(mut self) {
+        let reason = match self.serve() {
+            Ok(()) => "Connection closed".to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        self.closed.store(true, Ordering::Release);
+        for (_, pending) in self.pending.lock().drain() {
+            let error = NodeError::CouldNotReadMessageFromNode(reason.clone().into());
+            let _ = pending.reply.try_send(Err(error));
+        }
+        self.subscriptions.lock().clear();
+    }
+
+    /// The worker always keeps reading, so pings are answered and a close
+    /// from the node is noticed even while nothing is in flight. The read
+    /// timeout bounds how long a new request waits to be sent: short while
+    /// requests are pending, longer while the connection is idle.
+    fn serve(&mut self) -> Result<(), tungstenite::Error> {
+        loop {
+            loop {
+                match self.incoming.try_recv() {
+                    Ok(message) => self.socket.send(message)?,
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => return self.socket.close(None),
+                }
+            }
+
+            let idle = self.is_idle();
+            if idle != self.idle {
+                let timeout = match idle {
+                    true => consts::IDLE_READ_TIMEOUT,
+                    false => consts::READ_TIMEOUT,
+                };
+                set_read_timeout(&self.socket, timeout)?;
+                self.idle = idle;
+            }
+
+            match self.socket.read() {
+                Ok(Message::Text(text)) => self.dispatch(&text),
+                Ok(Message::Close(_)) => return Ok(()),
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn is_idle(&self) -> bool {
+        self.pending.lock().is_empty() && self.subscriptions.lock().is_empty()
+    }
+
+    fn dispatch(&self, text: &str) {
+        match serde_json::from_str::<Value>(text) {
+            Ok(Value::Array(responses)) => responses
+                .into_iter()
+                .for_each(|response| self.dispatch_one(response)),
+            Ok(response) => self.dispatch_one(response),
+            Err(_) => {}
+        }
+    }
+
+    /// A batch the node rejects as a whole gets one error object without an
+    /// id. It can't tell which batch it belongs to, so every batch in flight
+    /// fails with it and is retried rather than waiting for the timeout.
+    fn reject_batches(&self, response: &Value) {
+        let error = match serde_json::from_value::<RpcError>(response["error"].clone()) {
+            Ok(error) => error,
+            Err(_) => return,
+        };
+
+        let mut pending = self.pending.lock();
+        let ids: Vec<u64> = pending
+            .iter()
+            .filter(|(_, pending)| pending.in_batch)
+            .map(|(id, _)| *id)
+            .collect();
+        for id in ids {
+            if let Some(pending) = pending.remove(&id) {
+                let e = error.clone().into_node_error(batch::consts::METHOD);
+                let _ = pending.reply.try_send(Err(e));
+            }
+        }
+    }
+
+    fn dispatch_one(&self, mut response: Value) {
+        if let Some(id) = subscription_id(&response["params"]["subscription"]) {
+            if let Some(notify) = self.subscriptions.lock().get(&id) {
+                let _ = notify.try_send(response["params"]["result"].take());
+            }
+            return;
+        }
+
+        let pending = match response["id"].as_u64() {
+            Some(id) => self.pending.lock().remove(&id),
+            None => return self.reject_batches(&response),
+        };
+
+        if let Some(pending) = pending {
+            if let (Some(notify), Some(id)) = (pending.notify, subscription_id(&response["result"]))
+            {
+                self.subscriptions.lock().insert(id, notify);
+            }
+            response["id"] = pending.caller_id;
+            let _ = pending.reply.try_send(Ok(response.to_string()));
+        }
+    }
+}
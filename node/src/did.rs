@@ -0,0 +1,70 @@
+use codec::{Decode, Encode};
+use sp_core::crypto::AccountId32;
+use sp_core::hashing::blake2_256;
+
+use crate::errors::NodeError;
+use crate::metadata::Metadata;
+use crate::storage::StorageQuery;
+
+pub mod consts {
+    pub const PEAQ_DID: &str = "PeaqDid";
+    pub const ATTRIBUTE_STORE: &str = "AttributeStore";
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct Attribute {
+    pub name: Vec<u8>,
+    pub value: Vec<u8>,
+    pub validity: u32,
+    pub created: u64,
+}
+
+pub struct Did;
+
+impl Did {
+    /// The pallet keys attributes by a hash of the DID account and the name.
+    pub fn attribute_key(did_account: &AccountId32, name: &[u8]) -> [u8; 32] {
+        let mut bytes = did_account.encode();
+        bytes.extend(name);
+        blake2_256(&bytes)
+    }
+
+    pub async fn read_attribute(
+        url: &str,
+        did_account: &AccountId32,
+        name: &[u8],
+    ) -> Result<Option<Attribute>, NodeError> {
+        let metadata = Metadata::new(url).await?;
+        let key = Did::attribute_key(did_account, name);
+        let query =
+            StorageQuery::new(&metadata, consts::PEAQ_DID, consts::ATTRIBUTE_STORE)?.key(&key);
+
+        // Missing attributes come back as the storage default, an empty one
+        match query.fetch::<Attribute>(url).await? {
+            Some(attribute) if !attribute.name.is_empty() => Ok(Some(attribute)),
+            _ => Ok(None),
+        }
+    }
+
+    /// The store is keyed by a hash of the account and the name, which has
+    /// no account prefix, so this scans every attribute on chain and keeps
+    /// those whose key matches `did_account`.
+    pub async fn attributes(
+        url: &str,
+        did_account: &AccountId32,
+    ) -> Result<Vec<Attribute>, NodeError> {
+        let metadata = Metadata::new(url).await?;
+        let query = StorageQuery::new(&metadata, consts::PEAQ_DID, consts::ATTRIBUTE_STORE)?;
+
+        let attributes = query
+            .entries::<Attribute>(url)
+            .await?
+            .into_iter()
+            .filter(|(key, attribute)| {
+                key.ends_with(&Did::attribute_key(did_account, &attribute.name))
+            })
+            .map(|(_, attribute)| attribute)
+            .collect();
+        Ok(attributes)
+    }
+}
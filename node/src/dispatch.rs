@@ -0,0 +1,150 @@
+use sp_runtime::{ArithmeticError, DispatchError, ModuleError, TokenError, TransactionalError};
+use std::fmt;
+
+use crate::errors::NodeError;
+use crate::metadata::Metadata;
+use crate::value::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleErrorDetails {
+    pub pallet: String,
+    pub name: String,
+    pub docs: String,
+}
+
+/// A `DispatchError` together with the pallet error it stands for, when it
+/// is a module error the metadata knows about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DispatchFailure {
+    pub error: DispatchError,
+    pub details: Option<ModuleErrorDetails>,
+}
+
+impl DispatchFailure {
+    pub fn resolve(metadata: &Metadata, error: DispatchError) -> Self {
+        let details = match &error {
+            DispatchError::Module(module) => metadata
+                .pallet_error(module.index, module.error[0])
+                .map(|(pallet, variant)| ModuleErrorDetails {
+                    pallet: pallet.to_string(),
+                    name: variant.name.clone(),
+                    docs: variant.docs.join(" "),
+                }),
+            _ => None,
+        };
+
+        DispatchFailure { error, details }
+    }
+
+    /// Resolves a `DispatchError` decoded by the runtime's own type for it.
+    /// Variants are matched by name, so a runtime that orders them
+    /// differently from `sp_runtime` still reads correctly.
+    pub fn from_value(metadata: &Metadata, value: &Value) -> Result<Self, NodeError> {
+        match dispatch_error(value) {
+            Some(error) => Ok(DispatchFailure::resolve(metadata, error)),
+            None => {
+                let error = format!("Unknown dispatch error {:?}", value);
+                Err(NodeError::CouldNotDecodeEvents(error.into()))
+            }
+        }
+    }
+}
+
+fn dispatch_error(value: &Value) -> Option<DispatchError> {
+    let (name, inner) = variant(value)?;
+    let error = match name {
+        "Other" => DispatchError::Other(""),
+        "CannotLookup" => DispatchError::CannotLookup,
+        "BadOrigin" => DispatchError::BadOrigin,
+        "Module" => DispatchError::Module(module_error(inner?)?),
+        "ConsumerRemaining" => DispatchError::ConsumerRemaining,
+        "NoProviders" => DispatchError::NoProviders,
+        "TooManyConsumers" => DispatchError::TooManyConsumers,
+        "Token" => DispatchError::Token(token_error(inner?)?),
+        "Arithmetic" => DispatchError::Arithmetic(arithmetic_error(inner?)?),
+        "Transactional" => DispatchError::Transactional(transactional_error(inner?)?),
+        "Exhausted" => DispatchError::Exhausted,
+        "Corruption" => DispatchError::Corruption,
+        "Unavailable" => DispatchError::Unavailable,
+        "RootNotAllowed" => DispatchError::RootNotAllowed,
+        _ => return None,
+    };
+    Some(error)
+}
+
+/// The variant's name and, for a variant wrapping a single value, that
+/// value. `Module` was a struct variant before it wrapped `ModuleError`.
+fn variant(value: &Value) -> Option<(&str, Option<&Value>)> {
+    match value {
+        Value::Variant { name, fields, .. } => match fields.as_slice() {
+            [(None, inner)] => Some((name, Some(inner))),
+            [] => Some((name, None)),
+            _ => Some((name, Some(value))),
+        },
+        _ => None,
+    }
+}
+
+/// Older runtimes encode the pallet error as a single byte rather than four.
+fn module_error(value: &Value) -> Option<ModuleError> {
+    let index = value.field("index")?.as_u128()? as u8;
+    let error = match value.field("error")? {
+        Value::Bytes(bytes) => <[u8; 4]>::try_from(bytes.as_slice()).ok()?,
+        Value::Unsigned(error) => [*error as u8, 0, 0, 0],
+        _ => return None,
+    };
+
+    Some(ModuleError {
+        index,
+        error,
+        message: None,
+    })
+}
+
+fn token_error(value: &Value) -> Option<TokenError> {
+    let error = match variant(value)?.0 {
+        "FundsUnavailable" => TokenError::FundsUnavailable,
+        "OnlyProvider" => TokenError::OnlyProvider,
+        "BelowMinimum" => TokenError::BelowMinimum,
+        "CannotCreate" => TokenError::CannotCreate,
+        "UnknownAsset" => TokenError::UnknownAsset,
+        "Frozen" => TokenError::Frozen,
+        "Unsupported" => TokenError::Unsupported,
+        "CannotCreateHold" => TokenError::CannotCreateHold,
+        "NotExpendable" => TokenError::NotExpendable,
+        "Blocked" => TokenError::Blocked,
+        _ => return None,
+    };
+    Some(error)
+}
+
+fn arithmetic_error(value: &Value) -> Option<ArithmeticError> {
+    let error = match variant(value)?.0 {
+        "Underflow" => ArithmeticError::Underflow,
+        "Overflow" => ArithmeticError::Overflow,
+        "DivisionByZero" => ArithmeticError::DivisionByZero,
+        _ => return None,
+    };
+    Some(error)
+}
+
+fn transactional_error(value: &Value) -> Option<TransactionalError> {
+    let error = match variant(value)?.0 {
+        "LimitReached" => TransactionalError::LimitReached,
+        "NoLayer" => TransactionalError::NoLayer,
+        _ => return None,
+    };
+    Some(error)
+}
+
+impl fmt::Display for DispatchFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.details {
+            Some(details) if details.docs.is_empty() => {
+                write!(f, "{}::{}", details.pallet, details.name)
+            }
+            Some(details) => write!(f, "{}::{}: {}", details.pallet, details.name, details.docs),
+            None => write!(f, "{:?}", self.error),
+        }
+    }
+}
@@ -0,0 +1,216 @@
+use crate::calls::transport::Transport;
+use crate::errors::NodeError;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+use std::time::{Duration, Instant};
+
+pub mod consts {
+    use std::time::Duration;
+
+    pub const COOLDOWN: Duration = Duration::from_secs(5);
+    pub const MAX_COOLDOWN: Duration = Duration::from_secs(60);
+}
+
+#[derive(Debug, Clone, Default)]
+struct Health {
+    failures: u32,
+    down_until: Option<Instant>,
+    latency: Option<Duration>,
+}
+
+impl Health {
+    fn is_up(&self, now: Instant) -> bool {
+        !matches!(self.down_until, Some(until) if until > now)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointHealth {
+    pub url: String,
+    pub up: bool,
+    pub failures: u32,
+    pub latency: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Registry {
+    networks: HashMap<String, Vec<String>>,
+    health: HashMap<String, Health>,
+}
+
+async_std::task_local! {
+    static PINNED: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Unpins the network when the pinned operation ends or is dropped.
+struct Pin(Option<String>);
+
+impl Drop for Pin {
+    fn drop(&mut self) {
+        if let Some(url) = self.0.take() {
+            let _ = PINNED.try_with(|pinned| pinned.borrow_mut().remove(&url));
+        }
+    }
+}
+
+fn registry() -> MutexGuard<'static, Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY
+        .get_or_init(|| Mutex::new(Registry::default()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Fallback endpoints per network, keyed by the url of the preferred one.
+/// Everything in the crate keeps addressing a network by that url, and each
+/// request is routed to the preferred endpoint while it is healthy. An
+/// endpoint that fails is skipped for a cooldown that doubles with every
+/// failure in a row, after which the next request tries it again.
+/// Fallbacks only stand in for an endpoint of the same transport, so callers
+/// can keep choosing the transport from the url.
+pub struct Endpoints;
+
+impl Endpoints {
+    pub fn register(url: &str, fallbacks: Vec<String>) {
+        let transport = Transport::from_url(url);
+        let mut endpoints = vec![url.to_string()];
+        endpoints.extend(
+            fallbacks
+                .into_iter()
+                .filter(|fallback| fallback != url && Transport::from_url(fallback) == transport),
+        );
+        registry().networks.insert(url.to_string(), endpoints);
+    }
+
+    pub fn list(url: &str) -> Vec<String> {
+        match registry().networks.get(url) {
+            Some(endpoints) => endpoints.clone(),
+            None => vec![url.to_string()],
+        }
+    }
+
+    /// The first healthy endpoint in order of preference. If the preferred
+    /// one is down, the fastest healthy fallback is used instead, and when
+    /// everything is down, the endpoint that has been down the longest.
+    pub fn select(url: &str) -> String {
+        let endpoints = Endpoints::list(url);
+        let registry = registry();
+        let health = |endpoint: &String| registry.health.get(endpoint).cloned().unwrap_or_default();
+        let now = Instant::now();
+
+        if health(&endpoints[0]).is_up(now) {
+            return endpoints[0].clone();
+        }
+
+        let fallback = endpoints
+            .iter()
+            .skip(1)
+            .filter(|endpoint| health(endpoint).is_up(now))
+            .min_by_key(|endpoint| health(endpoint).latency.unwrap_or(Duration::MAX));
+        if let Some(fallback) = fallback {
+            return fallback.clone();
+        }
+
+        match endpoints
+            .iter()
+            .min_by_key(|endpoint| health(endpoint).down_until)
+        {
+            Some(endpoint) => endpoint.clone(),
+            None => url.to_string(),
+        }
+    }
+
+    pub fn health(url: &str) -> Vec<EndpointHealth> {
+        let endpoints = Endpoints::list(url);
+        let registry = registry();
+        let now = Instant::now();
+
+        endpoints
+            .into_iter()
+            .map(|endpoint| {
+                let health = registry.health.get(&endpoint).cloned().unwrap_or_default();
+                EndpointHealth {
+                    up: health.is_up(now),
+                    failures: health.failures,
+                    latency: health.latency,
+                    url: endpoint,
+                }
+            })
+            .collect()
+    }
+
+    /// Runs `operation` with every request to `url`'s network going to one
+    /// endpoint, for operations whose calls build on each other, such as
+    /// signing against a block hash and then submitting. The endpoint stays
+    /// pinned for the whole operation even if it fails. Pins are per task,
+    /// and an operation inside a pinned one keeps the outer pin.
+    pub async fn pinned<T, Fut>(url: &str, operation: Fut) -> T
+    where
+        Fut: Future<Output = T>,
+    {
+        let pinned = PINNED.try_with(|pinned| {
+            let mut pinned = pinned.borrow_mut();
+            if pinned.contains_key(url) {
+                return false;
+            }
+            pinned.insert(url.to_string(), Endpoints::select(url));
+            true
+        });
+
+        let _pin = Pin(matches!(pinned, Ok(true)).then(|| url.to_string()));
+        operation.await
+    }
+
+    /// Runs `operation` against the selected endpoint of `url`'s network, or
+    /// the pinned one, and records how it went. Only errors that say
+    /// something about the endpoint itself, network failures and rate
+    /// limiting, count against it.
+    pub async fn route<T, F, Fut>(url: &str, operation: F) -> Result<T, NodeError>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: Future<Output = Result<T, NodeError>>,
+    {
+        let pinned = PINNED
+            .try_with(|pinned| pinned.borrow().get(url).cloned())
+            .ok()
+            .flatten();
+        let endpoint = match pinned {
+            Some(endpoint) => endpoint,
+            None => Endpoints::select(url),
+        };
+        let started = Instant::now();
+        let result = operation(endpoint.clone()).await;
+
+        match &result {
+            Ok(_) => Endpoints::succeeded(&endpoint, started.elapsed()),
+            Err(e) if e.is_retryable() => Endpoints::failed(&endpoint),
+            Err(_) => {}
+        }
+        result
+    }
+
+    fn succeeded(endpoint: &str, latency: Duration) {
+        let mut registry = registry();
+        let health = registry.health.entry(endpoint.to_string()).or_default();
+        health.failures = 0;
+        health.down_until = None;
+        health.latency = match health.latency {
+            Some(average) => Some((average * 3 + latency) / 4),
+            None => Some(latency),
+        };
+    }
+
+    fn failed(endpoint: &str) {
+        let mut registry = registry();
+        let health = registry.health.entry(endpoint.to_string()).or_default();
+        health.failures += 1;
+
+        let factor = 2u32.saturating_pow(health.failures - 1);
+        let cooldown = consts::COOLDOWN
+            .saturating_mul(factor)
+            .min(consts::MAX_COOLDOWN);
+        health.down_until = Some(Instant::now() + cooldown);
+    }
+}
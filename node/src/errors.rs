@@ -1,46 +1,156 @@
+use crate::dispatch::DispatchFailure;
+use sp_runtime::transaction_validity::TransactionValidityError;
 use thiserror::Error;
 
+pub type Source = Box<dyn std::error::Error + Send + Sync>;
+
+pub mod consts {
+    // JSON-RPC error codes of the transaction pool and the RPC server
+    pub const POOL_INVALID_TX: i64 = 1010;
+    pub const POOL_ALREADY_IMPORTED: i64 = 1013;
+    pub const SERVER_IS_BUSY: i64 = -32009;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    TransientNetwork,
+    RateLimited,
+    NodeRejected,
+    InvalidTransaction,
+    Decode,
+}
+
+impl ErrorKind {
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ErrorKind::TransientNetwork | ErrorKind::RateLimited)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum NodeError {
-    #[error("Cound not connect to the blockchain node\n{0}")]
-    CouldNotConnectToNode(String),
+    #[error("Cound not connect to the blockchain node")]
+    CouldNotConnectToNode(#[source] Source),
     #[error("Cound not close socket connection")]
     CouldNotCloseSocketConnection,
-    #[error("Cound not send message to the node\n{0}")]
-    CouldNotSendMessageToNode(String),
-    #[error("Cound not read a response from the blockchain node\n{0}")]
-    CouldNotReadMessageFromNode(String),
-    #[error("Cound not get account nonce\n{0}")]
-    CouldNotGetAccountNonce(String),
-    #[error("Cound not get genesis hash\n{0}")]
-    CouldNotGetGenesisHash(String),
-    #[error("Cound not parse genesis hash\n{0}")]
-    CouldNotParseGenesisHash(String),
-    #[error("Cound not get metadata\n{0}")]
-    CouldNotGetMetadata(String),
-    #[error("Cound not decode metadata from hex to bytes\n{0}")]
-    CouldNotDecodeMetadataHex(String),
-    #[error("Cound not decode metadata from bytes\n{0}")]
-    CouldNotDecodeMetadataBytes(String),
+    #[error("Cound not send message to the node")]
+    CouldNotSendMessageToNode(#[source] Source),
+    #[error("Cound not read a response from the blockchain node")]
+    CouldNotReadMessageFromNode(#[source] Source),
+    #[error(
+        "Node returned error {code} for {method}\n{message}{}",
+        .data.as_ref().map(|data| format!(". {}", data)).unwrap_or_default()
+    )]
+    Rpc {
+        code: i64,
+        message: String,
+        data: Option<String>,
+        method: String,
+    },
+    #[error("Node is rate limiting requests\n{0}")]
+    RateLimited(String),
+    #[error("Cound not subscribe to the node")]
+    CouldNotSubscribe(#[source] Source),
+    #[error("Subscriptions are only available over a WebSocket connection")]
+    SubscriptionRequiresWebSocket,
+    #[error("Cound not get account nonce")]
+    CouldNotGetAccountNonce(#[source] Source),
+    #[error("Cound not get genesis hash")]
+    CouldNotGetGenesisHash(#[source] Source),
+    #[error("Cound not parse genesis hash")]
+    CouldNotParseGenesisHash(#[source] Source),
+    #[error("Cound not get metadata")]
+    CouldNotGetMetadata(#[source] Source),
+    #[error("Cound not decode metadata from hex to bytes")]
+    CouldNotDecodeMetadataHex(#[source] Source),
+    #[error("Cound not decode metadata from bytes")]
+    CouldNotDecodeMetadataBytes(#[source] Source),
     #[error("Cound not decode latest metadata")]
     CouldNotDecodeMetadataLatest,
-    #[error("Cound not decode metadata v14\n{0}")]
-    CouldNotDecodeMetadataV14(String),
-    #[error("Cound not get runtime version\n{0}")]
-    CouldNotGetRuntimeVersion(String),
-    #[error("Cound not get runtime metadata\n{0}")]
-    CouldNotGetRuntimeMetadata(String),
-    #[error("Cound get block data\n{0}")]
-    CouldNotGetBlock(String),
-    #[error("Cound not get storage value\n{0}")]
-    CouldNotGetStorageValue(String),
-    #[error("Cound not send an extrinsic transaction\n{0}")]
-    CouldNotCallExtrinsic(String),
+    #[error("Cound not decode metadata v14")]
+    CouldNotDecodeMetadataV14(#[source] Source),
+    #[error("Cound not decode metadata v15")]
+    CouldNotDecodeMetadataV15(#[source] Source),
+    #[error("Cound not get runtime version")]
+    CouldNotGetRuntimeVersion(#[source] Source),
+    #[error("Cound not get runtime metadata")]
+    CouldNotGetRuntimeMetadata(#[source] Source),
+    #[error("Cound not call runtime API")]
+    CouldNotCallRuntimeApi(#[source] Source),
+    #[error("Cound get block data")]
+    CouldNotGetBlock(#[source] Source),
+    #[error("Cound not get block header")]
+    CouldNotGetHeader(#[source] Source),
+    #[error("Cound not decode events")]
+    CouldNotDecodeEvents(#[source] Source),
+    #[error("Cound not decode extrinsic")]
+    CouldNotDecodeExtrinsic(#[source] Source),
+    #[error("Cound not decode batch response")]
+    CouldNotDecodeBatch(#[source] Source),
+    #[error("Extrinsic failed to dispatch\n{0}")]
+    ExtrinsicFailed(DispatchFailure),
+    #[error("Transaction is invalid\n{0:?}")]
+    InvalidTransaction(TransactionValidityError),
+    #[error("Cound not find storage entry \n{0}")]
+    CouldNotGetStorageEntry(String),
+    #[error("Cound not decode value")]
+    CouldNotDecodeValue(#[source] Source),
+    #[error("Cound not get storage keys")]
+    CouldNotGetStorageKeys(#[source] Source),
+    #[error("Cound not get storage value")]
+    CouldNotGetStorageValue(#[source] Source),
+    #[error("Cound not send an extrinsic transaction")]
+    CouldNotCallExtrinsic(#[source] Source),
+    #[error("Cound not dry run the extrinsic")]
+    CouldNotDryRun(#[source] Source),
+    #[error("Cound not query the transaction fee")]
+    CouldNotQueryFee(#[source] Source),
+    #[error("Transaction fee exceeds the budget\n{0}")]
+    FeeExceedsBudget(String),
+    #[error("Signed extension is not supported\n{0}")]
+    UnsupportedSignedExtension(String),
+    #[error("Extrinsic was not included\n{0}")]
+    ExtrinsicNotIncluded(String),
+    #[error("Block was not finalized\n{0}")]
+    BlockNotFinalized(String),
     #[error("Cound not get index for pallet \n{0}")]
     CouldNotGetPalletIndex(String),
     #[error("Cound not get index for method \n{0}")]
     CouldNotGetMethodIndex(String),
+    #[error("Cound not get constant")]
+    CouldNotGetConstant(#[source] Source),
+
+    #[error("Cound not send HTTP request to the node")]
+    CouldNotSendHttpsRequest(#[source] Source),
+}
+
+impl NodeError {
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            NodeError::CouldNotConnectToNode(_)
+            | NodeError::CouldNotCloseSocketConnection
+            | NodeError::CouldNotSendMessageToNode(_)
+            | NodeError::CouldNotReadMessageFromNode(_)
+            | NodeError::CouldNotSendHttpsRequest(_) => ErrorKind::TransientNetwork,
+            NodeError::RateLimited(_) => ErrorKind::RateLimited,
+            NodeError::Rpc { code, .. } => match *code {
+                consts::SERVER_IS_BUSY => ErrorKind::RateLimited,
+                consts::POOL_INVALID_TX => ErrorKind::InvalidTransaction,
+                _ => ErrorKind::NodeRejected,
+            },
+            NodeError::SubscriptionRequiresWebSocket
+            | NodeError::ExtrinsicNotIncluded(_)
+            | NodeError::BlockNotFinalized(_) => ErrorKind::NodeRejected,
+            NodeError::ExtrinsicFailed(_)
+            | NodeError::InvalidTransaction(_)
+            | NodeError::FeeExceedsBudget(_)
+            | NodeError::UnsupportedSignedExtension(_)
+            | NodeError::CouldNotGetPalletIndex(_)
+            | NodeError::CouldNotGetMethodIndex(_) => ErrorKind::InvalidTransaction,
+            _ => ErrorKind::Decode,
+        }
+    }
 
-    #[error("Cound not send HTTP request to the node \n{0}")]
-    CouldNotSendHttpsRequest(String),
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_retryable()
+    }
 }
@@ -0,0 +1,286 @@
+use codec::Decode;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef};
+use sp_core::H256;
+
+use crate::calls::{block::Block, call::Call};
+use crate::dispatch::DispatchFailure;
+use crate::errors::NodeError;
+use crate::metadata::Metadata;
+use crate::storage::StorageQuery;
+use crate::value::{decode_type, Value};
+
+pub mod consts {
+    pub const SYSTEM: &str = "System";
+    pub const EVENTS: &str = "Events";
+    pub const EXTRINSIC_SUCCESS: &str = "ExtrinsicSuccess";
+    pub const EXTRINSIC_FAILED: &str = "ExtrinsicFailed";
+    pub const DISPATCH_ERROR: &str = "dispatch_error";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    ApplyExtrinsic(u32),
+    Finalization,
+    Initialization,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub pallet: String,
+    pub name: String,
+    pub fields: Vec<(Option<String>, Value)>,
+    bytes: Vec<u8>,
+}
+
+impl Event {
+    /// SCALE encoded fields of the event, for decoding into a known type.
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn is(&self, pallet: &str, name: &str) -> bool {
+        self.pallet.eq(pallet) && self.name.eq(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRecord {
+    pub phase: Phase,
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtrinsicOutcome {
+    Success,
+    Failed(DispatchFailure),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtrinsicEvents {
+    pub index: u32,
+    pub outcome: ExtrinsicOutcome,
+    pub events: Vec<Event>,
+}
+
+impl ExtrinsicEvents {
+    /// Looks `extrinsic`, hex encoded as it was submitted, up in the block
+    /// and returns what happened to it there.
+    pub async fn find(
+        url: &str,
+        block_hash: H256,
+        extrinsic: &str,
+    ) -> Result<ExtrinsicEvents, NodeError> {
+        let block = Block
+            .get(url, Some(vec![format!("{:?}", block_hash)]))
+            .await?;
+        let index = block
+            .extrinsics()
+            .iter()
+            .position(|pred| pred.eq_ignore_ascii_case(extrinsic));
+
+        let index = match index {
+            Some(index) => index as u32,
+            None => {
+                let error = format!("Not found in block {:?}", block_hash);
+                return Err(NodeError::ExtrinsicNotIncluded(error));
+            }
+        };
+
+        match Events::at(url, block_hash).await?.for_extrinsic(index)? {
+            Some(events) => Ok(events),
+            None => {
+                let error = format!("No outcome for extrinsic {}", index);
+                Err(NodeError::CouldNotDecodeEvents(error.into()))
+            }
+        }
+    }
+}
+
+pub struct Events {
+    metadata: Metadata,
+    records: Vec<EventRecord>,
+}
+
+impl Events {
+    /// Decodes with the metadata of the runtime that produced the block.
+    pub async fn at(url: &str, block_hash: H256) -> Result<Events, NodeError> {
+        let metadata = Metadata::at_block(url, block_hash).await?;
+        let query = StorageQuery::new(&metadata, consts::SYSTEM, consts::EVENTS)?.at(block_hash);
+        let bytes = query.fetch_raw(url).await?.unwrap_or_default();
+
+        match decode_records(&metadata.get().types, query.value_type(), &bytes) {
+            Ok(records) => Ok(Events { metadata, records }),
+            Err(e) => Err(NodeError::CouldNotDecodeEvents(e.into())),
+        }
+    }
+
+    pub fn records(&self) -> &[EventRecord] {
+        &self.records
+    }
+
+    /// Every event `index` emitted, and whether it dispatched successfully.
+    /// `None` if the block has no outcome for that extrinsic.
+    pub fn for_extrinsic(&self, index: u32) -> Result<Option<ExtrinsicEvents>, NodeError> {
+        let events: Vec<Event> = self
+            .records
+            .iter()
+            .filter(|record| record.phase == Phase::ApplyExtrinsic(index))
+            .map(|record| record.event.clone())
+            .collect();
+
+        let mut outcome = None;
+        for event in &events {
+            if event.is(consts::SYSTEM, consts::EXTRINSIC_SUCCESS) {
+                outcome = Some(ExtrinsicOutcome::Success);
+                break;
+            }
+            if event.is(consts::SYSTEM, consts::EXTRINSIC_FAILED) {
+                let failure = DispatchFailure::from_value(&self.metadata, dispatch_error(event)?)?;
+                outcome = Some(ExtrinsicOutcome::Failed(failure));
+                break;
+            }
+        }
+
+        Ok(outcome.map(|outcome| ExtrinsicEvents {
+            index,
+            outcome,
+            events,
+        }))
+    }
+}
+
+/// The `dispatch_error` field of `ExtrinsicFailed`, as the runtime's type
+/// for it decoded it.
+fn dispatch_error(event: &Event) -> Result<&Value, NodeError> {
+    let field = event
+        .fields
+        .iter()
+        .find(|(name, _)| name.as_deref() == Some(consts::DISPATCH_ERROR))
+        .or(event.fields.first());
+
+    match field {
+        Some((_, value)) => Ok(value),
+        None => {
+            let error = format!(
+                "{} has no {}",
+                consts::EXTRINSIC_FAILED,
+                consts::DISPATCH_ERROR
+            );
+            Err(NodeError::CouldNotDecodeEvents(error.into()))
+        }
+    }
+}
+
+fn decode_records(
+    registry: &PortableRegistry,
+    type_id: u32,
+    bytes: &[u8],
+) -> Result<Vec<EventRecord>, codec::Error> {
+    let record_ty = match registry.resolve(type_id).map(|ty| &ty.type_def) {
+        Some(TypeDef::Sequence(def)) => def.type_param.id,
+        _ => return Err("System.Events is not a sequence".into()),
+    };
+    let fields = match registry.resolve(record_ty).map(|ty| &ty.type_def) {
+        Some(TypeDef::Composite(def)) => &def.fields,
+        _ => return Err("Event record is not a struct".into()),
+    };
+
+    let input = &mut &bytes[..];
+    let len = codec::Compact::<u32>::decode(input)?.0;
+    let mut records = Vec::new();
+    for _ in 0..len {
+        records.push(decode_record(registry, fields, input)?);
+    }
+    Ok(records)
+}
+
+fn decode_record(
+    registry: &PortableRegistry,
+    fields: &[Field<PortableForm>],
+    input: &mut &[u8],
+) -> Result<EventRecord, codec::Error> {
+    let mut phase = None;
+    let mut event = None;
+    for field in fields {
+        match field.name.as_deref() {
+            Some("phase") => phase = Some(decode_phase(registry, field.ty.id, input)?),
+            Some("event") => event = Some(decode_event(registry, field.ty.id, input)?),
+            _ => {
+                decode_type(registry, field.ty.id, input)?;
+            }
+        }
+    }
+
+    match (phase, event) {
+        (Some(phase), Some(event)) => Ok(EventRecord { phase, event }),
+        _ => Err("Event record has no phase or event".into()),
+    }
+}
+
+fn decode_phase(
+    registry: &PortableRegistry,
+    type_id: u32,
+    input: &mut &[u8],
+) -> Result<Phase, codec::Error> {
+    match decode_type(registry, type_id, input)? {
+        Value::Variant { name, fields, .. } => match name.as_str() {
+            "ApplyExtrinsic" => match fields.first().and_then(|(_, index)| index.as_u128()) {
+                Some(index) => Ok(Phase::ApplyExtrinsic(index as u32)),
+                None => Err("Phase has no extrinsic index".into()),
+            },
+            "Finalization" => Ok(Phase::Finalization),
+            _ => Ok(Phase::Initialization),
+        },
+        _ => Err("Phase is not an enum".into()),
+    }
+}
+
+/// The runtime event is an enum of pallets, each wrapping an enum of that
+/// pallet's events. Both levels are unwrapped so the event keeps its own
+/// fields and their encoded bytes.
+fn decode_event(
+    registry: &PortableRegistry,
+    type_id: u32,
+    input: &mut &[u8],
+) -> Result<Event, codec::Error> {
+    let pallet = decode_variant(registry, type_id, input)?;
+    let pallet_event = match pallet.fields.first() {
+        Some(field) => field.ty.id,
+        None => return Err("Pallet event has no fields".into()),
+    };
+    let event = decode_variant(registry, pallet_event, input)?;
+
+    let start: &[u8] = input;
+    let mut fields = Vec::new();
+    for field in &event.fields {
+        fields.push((
+            field.name.clone(),
+            decode_type(registry, field.ty.id, input)?,
+        ));
+    }
+    let bytes = start[..start.len() - input.len()].to_vec();
+
+    Ok(Event {
+        pallet: pallet.name.clone(),
+        name: event.name.clone(),
+        fields,
+        bytes,
+    })
+}
+
+fn decode_variant<'a>(
+    registry: &'a PortableRegistry,
+    type_id: u32,
+    input: &mut &[u8],
+) -> Result<&'a scale_info::Variant<PortableForm>, codec::Error> {
+    let variants = match registry.resolve(type_id).map(|ty| &ty.type_def) {
+        Some(TypeDef::Variant(def)) => &def.variants,
+        _ => return Err("Event is not an enum".into()),
+    };
+
+    let index = u8::decode(input)?;
+    match variants.iter().find(|pred| pred.index == index) {
+        Some(variant) => Ok(variant),
+        None => Err("Event index is out of range".into()),
+    }
+}
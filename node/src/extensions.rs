@@ -0,0 +1,192 @@
+use codec::{Compact, Encode};
+use sp_core::H256;
+use sp_runtime::generic::Era;
+
+use crate::errors::NodeError;
+use crate::metadata::Metadata;
+
+pub mod consts {
+    pub const CHECK_NON_ZERO_SENDER: &str = "CheckNonZeroSender";
+    pub const CHECK_SPEC_VERSION: &str = "CheckSpecVersion";
+    pub const CHECK_TX_VERSION: &str = "CheckTxVersion";
+    pub const CHECK_GENESIS: &str = "CheckGenesis";
+    pub const CHECK_MORTALITY: &str = "CheckMortality";
+    pub const CHECK_ERA: &str = "CheckEra";
+    pub const CHECK_NONCE: &str = "CheckNonce";
+    pub const CHECK_WEIGHT: &str = "CheckWeight";
+    pub const CHARGE_TRANSACTION_PAYMENT: &str = "ChargeTransactionPayment";
+    pub const CHARGE_ASSET_TX_PAYMENT: &str = "ChargeAssetTxPayment";
+    pub const CHECK_METADATA_HASH: &str = "CheckMetadataHash";
+}
+
+/// Everything the known signed extensions need to encode themselves.
+pub struct ExtensionParams {
+    pub era: Era,
+    pub checkpoint: H256,
+    pub nonce: u32,
+    pub tip: u128,
+    /// SCALE encoded id of the asset that pays the fee, if not the native token.
+    pub fee_asset: Option<Vec<u8>>,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+    pub genesis_hash: H256,
+}
+
+pub trait SignedExtension {
+    const IDENTIFIER: &'static str;
+
+    fn extra(&self, _params: &ExtensionParams, _out: &mut Vec<u8>) {}
+
+    fn additional(&self, _params: &ExtensionParams, _out: &mut Vec<u8>) {}
+}
+
+pub struct CheckNonZeroSender;
+pub struct CheckSpecVersion;
+pub struct CheckTxVersion;
+pub struct CheckGenesis;
+pub struct CheckMortality;
+pub struct CheckNonce;
+pub struct CheckWeight;
+pub struct ChargeTransactionPayment;
+pub struct ChargeAssetTxPayment;
+pub struct CheckMetadataHash;
+
+impl SignedExtension for CheckNonZeroSender {
+    const IDENTIFIER: &'static str = consts::CHECK_NON_ZERO_SENDER;
+}
+
+impl SignedExtension for CheckSpecVersion {
+    const IDENTIFIER: &'static str = consts::CHECK_SPEC_VERSION;
+
+    fn additional(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        params.spec_version.encode_to(out);
+    }
+}
+
+impl SignedExtension for CheckTxVersion {
+    const IDENTIFIER: &'static str = consts::CHECK_TX_VERSION;
+
+    fn additional(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        params.transaction_version.encode_to(out);
+    }
+}
+
+impl SignedExtension for CheckGenesis {
+    const IDENTIFIER: &'static str = consts::CHECK_GENESIS;
+
+    fn additional(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        params.genesis_hash.encode_to(out);
+    }
+}
+
+impl SignedExtension for CheckMortality {
+    const IDENTIFIER: &'static str = consts::CHECK_MORTALITY;
+
+    fn extra(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        params.era.encode_to(out);
+    }
+
+    fn additional(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        params.checkpoint.encode_to(out);
+    }
+}
+
+impl SignedExtension for CheckNonce {
+    const IDENTIFIER: &'static str = consts::CHECK_NONCE;
+
+    fn extra(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        Compact(params.nonce).encode_to(out);
+    }
+}
+
+impl SignedExtension for CheckWeight {
+    const IDENTIFIER: &'static str = consts::CHECK_WEIGHT;
+}
+
+impl SignedExtension for ChargeTransactionPayment {
+    const IDENTIFIER: &'static str = consts::CHARGE_TRANSACTION_PAYMENT;
+
+    fn extra(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        Compact(params.tip).encode_to(out);
+    }
+}
+
+impl SignedExtension for ChargeAssetTxPayment {
+    const IDENTIFIER: &'static str = consts::CHARGE_ASSET_TX_PAYMENT;
+
+    fn extra(&self, params: &ExtensionParams, out: &mut Vec<u8>) {
+        Compact(params.tip).encode_to(out);
+        match &params.fee_asset {
+            Some(asset) => {
+                1u8.encode_to(out);
+                out.extend(asset);
+            }
+            None => 0u8.encode_to(out),
+        }
+    }
+}
+
+/// Always signs with the metadata hash check disabled.
+impl SignedExtension for CheckMetadataHash {
+    const IDENTIFIER: &'static str = consts::CHECK_METADATA_HASH;
+
+    fn extra(&self, _params: &ExtensionParams, out: &mut Vec<u8>) {
+        0u8.encode_to(out);
+    }
+
+    fn additional(&self, _params: &ExtensionParams, out: &mut Vec<u8>) {
+        None::<[u8; 32]>.encode_to(out);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct EncodedExtensions {
+    pub extra: Vec<u8>,
+    pub additional: Vec<u8>,
+}
+
+impl EncodedExtensions {
+    /// Encodes every signed extension listed by the runtime, in the runtime's
+    /// order, and fails on the first one this crate does not know.
+    pub fn new(metadata: &Metadata, params: &ExtensionParams) -> Result<Self, NodeError> {
+        let extensions = &metadata.get().extrinsic.signed_extensions;
+        let pays_in_assets = extensions
+            .iter()
+            .any(|pred| pred.identifier.eq(consts::CHARGE_ASSET_TX_PAYMENT));
+        if params.fee_asset.is_some() && !pays_in_assets {
+            let error = consts::CHARGE_ASSET_TX_PAYMENT.to_string();
+            return Err(NodeError::UnsupportedSignedExtension(error));
+        }
+
+        let mut encoded = EncodedExtensions::default();
+        for extension in extensions {
+            encoded.push(&extension.identifier, params)?;
+        }
+        Ok(encoded)
+    }
+
+    fn push(&mut self, identifier: &str, params: &ExtensionParams) -> Result<(), NodeError> {
+        match identifier {
+            consts::CHECK_NON_ZERO_SENDER => self.encode(CheckNonZeroSender, params),
+            consts::CHECK_SPEC_VERSION => self.encode(CheckSpecVersion, params),
+            consts::CHECK_TX_VERSION => self.encode(CheckTxVersion, params),
+            consts::CHECK_GENESIS => self.encode(CheckGenesis, params),
+            consts::CHECK_MORTALITY | consts::CHECK_ERA => self.encode(CheckMortality, params),
+            consts::CHECK_NONCE => self.encode(CheckNonce, params),
+            consts::CHECK_WEIGHT => self.encode(CheckWeight, params),
+            consts::CHARGE_TRANSACTION_PAYMENT => self.encode(ChargeTransactionPayment, params),
+            consts::CHARGE_ASSET_TX_PAYMENT => self.encode(ChargeAssetTxPayment, params),
+            consts::CHECK_METADATA_HASH => self.encode(CheckMetadataHash, params),
+            _ => {
+                let error = identifier.to_string();
+                return Err(NodeError::UnsupportedSignedExtension(error));
+            }
+        }
+        Ok(())
+    }
+
+    fn encode<E: SignedExtension>(&mut self, extension: E, params: &ExtensionParams) {
+        extension.extra(params, &mut self.extra);
+        extension.additional(params, &mut self.additional);
+    }
+}
@@ -1,58 +1,342 @@
-use codec::{Codec, Compact, Encode};
+use async_std::task;
+use codec::{Compact, Decode, Encode, Output};
 use sp_core::crypto::AccountId32;
 use sp_core::sr25519;
-use sp_core::Pair;
+use sp_core::H256;
 use sp_runtime::{generic::Era, MultiAddress, MultiSignature};
 
 use crate::calls::{
-    block_hash::BlockHash, call::Call, nonce::Nonce, runtime_version::RuntimeVersion,
+    batch::Batch,
+    block_hash::BlockHash,
+    call::Call,
+    dry_run::{DryRun, DryRunOutcome},
+    extrinsic::Extrinsic as Submit,
+    finalized_head::FinalizedHead,
+    header::Header,
+    nonce::Nonce,
+    query_info::{DispatchInfo, QueryInfo},
+    runtime_version::RuntimeVersion,
+    submit_and_watch::{ExtrinsicWatch, SubmitAndWatch},
 };
 
-use crate::errors::NodeError;
-use crate::metadata::Searchable;
-use crate::metadata::{Method, Pallet};
+use crate::did;
+use crate::endpoints::Endpoints;
+use crate::errors::{consts as rpc, ErrorKind, NodeError};
+use crate::extensions::{EncodedExtensions, ExtensionParams};
+use crate::finality::{self, Inclusion};
+use crate::metadata::Metadata;
+use crate::nonces::NonceManager;
+use crate::signer::Signer;
 
-pub struct Extrinsic<T: ExtrinsicCall> {
-    pair: sr25519::Pair,
+pub mod consts {
+    pub const DEFAULT_PERIOD: u64 = 64;
+    pub const SYSTEM: &str = "System";
+    pub const BLOCK_HASH_COUNT: &str = "BlockHashCount";
+    pub const UTILITY: &str = "Utility";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Checkpoint {
+    Best,
+    Finalized,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mortality {
+    Immortal,
+    Mortal {
+        period: Option<u64>,
+        checkpoint: Checkpoint,
+    },
+}
+
+impl Default for Mortality {
+    fn default() -> Self {
+        Mortality::Mortal {
+            period: None,
+            checkpoint: Checkpoint::Best,
+        }
+    }
+}
+
+pub struct Extrinsic<T: ExtrinsicCall, S: Signer = sr25519::Pair> {
+    signer: S,
     call: T::Call,
+    mortality: Mortality,
+    tip: u128,
+    fee_asset: Option<Vec<u8>>,
+}
+
+impl<T: ExtrinsicCall, S: Signer> Extrinsic<T, S> {
+    pub fn owner(&self) -> AccountId32 {
+        self.signer.account_id()
+    }
+
+    pub fn mortal(mut self, period: Option<u64>, checkpoint: Checkpoint) -> Self {
+        self.mortality = Mortality::Mortal { period, checkpoint };
+        self
+    }
+
+    pub fn immortal(mut self) -> Self {
+        self.mortality = Mortality::Immortal;
+        self
+    }
+
+    pub fn tip(mut self, tip: u128) -> Self {
+        self.tip = tip;
+        self
+    }
+
+    /// Pays the fee and tip in `asset` through `ChargeAssetTxPayment`.
+    pub fn fee_asset<A: Encode>(mut self, asset: A) -> Self {
+        self.fee_asset = Some(asset.encode());
+        self
+    }
+}
+
+/// A signed extrinsic and the hash the chain will know it by. Its nonce is
+/// fixed, so submitting the same bytes again can never include it twice.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedExtrinsic {
+    pub bytes: Vec<u8>,
+    pub hash: H256,
+    pub nonce: u32,
+    pub era: Era,
+    pub signed_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Submission {
+    Submitted(H256),
+    Included(Inclusion),
+}
+
+pub enum Watched {
+    Watching(ExtrinsicWatch),
+    Included(Inclusion),
 }
 
-impl<T: ExtrinsicCall> Extrinsic<T> {
-    fn owner(&self) -> AccountId32 {
-        AccountId32::from(self.pair.public())
+impl SignedExtrinsic {
+    /// `bytes` is the whole encoded extrinsic, length prefix included, which
+    /// is also what the chain hashes.
+    pub fn new(bytes: Vec<u8>, nonce: u32, era: Era, signed_at: u64) -> Self {
+        SignedExtrinsic {
+            hash: H256(sp_core::blake2_256(&bytes)),
+            bytes,
+            nonce,
+            era,
+            signed_at,
+        }
+    }
+
+    pub fn hex(&self) -> String {
+        format!("0x{}", hex::encode(&self.bytes))
+    }
+
+    /// Waits until the extrinsic's era is over, after which no block can
+    /// include it any more. Returns `false` at once for an immortal one,
+    /// which never expires.
+    pub async fn wait_for_expiry(&self, url: &str) -> Result<bool, NodeError> {
+        if let Era::Immortal = self.era {
+            return Ok(false);
+        }
+
+        let death = self.era.death(self.signed_at);
+        while Header.get(url, None).await?.number < death {
+            task::sleep(finality::consts::POLL_INTERVAL).await;
+        }
+        Ok(true)
+    }
+
+    /// Looks for the extrinsic in the blocks built since it was signed, up
+    /// to the block its era expires at. An immortal one is looked for up to
+    /// the best block.
+    pub async fn find(&self, url: &str) -> Result<Option<Inclusion>, NodeError> {
+        let blocks = match self.era {
+            Era::Immortal => u64::MAX,
+            Era::Mortal(..) => self
+                .era
+                .death(self.signed_at)
+                .saturating_sub(self.signed_at),
+        };
+        Inclusion::search(url, self.hash, self.signed_at, blocks).await
+    }
+
+    /// Submits the extrinsic. Safe to call again after a lost response or
+    /// a timeout: the pool either still has it, or the chain included it and
+    /// the node rejects the resubmission's spent nonce, in which case the
+    /// extrinsic is looked for in the blocks since it was signed.
+    pub async fn submit(&self, url: &str) -> Result<Submission, NodeError> {
+        Endpoints::pinned(url, self.submit_pinned(url)).await
+    }
+
+    /// Like `submit`, but follows the extrinsic's status, which needs a
+    /// WebSocket endpoint.
+    pub async fn submit_and_watch(&self, url: &str) -> Result<Watched, NodeError> {
+        Endpoints::pinned(url, self.submit_and_watch_pinned(url)).await
+    }
+
+    async fn submit_pinned(&self, url: &str) -> Result<Submission, NodeError> {
+        match Submit.get(url, Some(vec![self.hex()])).await {
+            Ok(_) => Ok(Submission::Submitted(self.hash)),
+            Err(NodeError::Rpc { code, .. }) if code == rpc::POOL_ALREADY_IMPORTED => {
+                Ok(Submission::Submitted(self.hash))
+            }
+            Err(e) if self.may_be_included(&e) => match self.find(url).await? {
+                Some(inclusion) => Ok(Submission::Included(inclusion)),
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// A pool that already has the extrinsic can't watch it again, so that
+    /// is looked into like a spent nonce.
+    async fn submit_and_watch_pinned(&self, url: &str) -> Result<Watched, NodeError> {
+        match SubmitAndWatch.get(url, Some(vec![self.hex()])).await {
+            Ok(watch) => Ok(Watched::Watching(watch)),
+            Err(e) if self.may_be_included(&e) => match self.find(url).await? {
+                Some(inclusion) => Ok(Watched::Included(inclusion)),
+                None => Err(e),
+            },
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Whether a failed submission may have reached the chain after all: its
+    /// response got lost, or the node already knows the extrinsic.
+    fn may_be_included(&self, error: &NodeError) -> bool {
+        match error {
+            NodeError::Rpc { code, .. } => {
+                *code == rpc::POOL_INVALID_TX || *code == rpc::POOL_ALREADY_IMPORTED
+            }
+            e => e.kind() == ErrorKind::TransientNetwork,
+        }
     }
 }
 
-impl<T: ExtrinsicCall> Extrinsic<T> {
+impl<T: ExtrinsicCall, S: Signer> Extrinsic<T, S> {
     pub async fn build(&self, url: &str) -> Result<String, NodeError> {
-        let nonce = Nonce.get(url, Some(vec![self.owner().to_string()])).await?;
-        let genesis_hash = BlockHash.get(url, Some(vec!["0".to_string()])).await?;
-        let runtime = RuntimeVersion.get(url, None).await?;
-        let indexes = T::indexes(url).await?;
-
-        let extra = (Era::Immortal, Compact(nonce), Compact(0u128));
-        let additional = (
-            runtime.spec_version,
-            runtime.transaction_version,
-            genesis_hash,
+        Ok(self.build_signed(url).await?.hex())
+    }
+
+    pub async fn build_signed(&self, url: &str) -> Result<SignedExtrinsic, NodeError> {
+        Endpoints::pinned(url, self.sign(url, true)).await
+    }
+
+    /// Signs the extrinsic exactly like `build` does, with the account's
+    /// current chain nonce, and asks the node what it would cost.
+    pub async fn estimate_fee(&self, url: &str) -> Result<DispatchInfo, NodeError> {
+        Endpoints::pinned(url, async {
+            let extrinsic = self.sign(url, false).await?;
+            QueryInfo::estimate(url, &extrinsic.bytes).await
+        })
+        .await
+    }
+
+    /// Applies the extrinsic on top of the best block without broadcasting it.
+    pub async fn dry_run(&self, url: &str) -> Result<DryRunOutcome, NodeError> {
+        Endpoints::pinned(url, async {
+            let extrinsic = self.sign(url, false).await?;
+            DryRun::run(url, &extrinsic.bytes).await
+        })
+        .await
+    }
+
+    async fn sign(&self, url: &str, reserve_nonce: bool) -> Result<SignedExtrinsic, NodeError> {
+        let mut batch = Batch::new();
+        let genesis = batch.push(&BlockHash, Some(vec!["0".to_string()]));
+        let best_header = batch.push(&Header, None);
+        let runtime = batch.push(&RuntimeVersion, None);
+        let nonce = batch.push(&Nonce, Some(vec![self.owner().to_string()]));
+        let response = batch.send(url).await?;
+
+        let genesis_hash = response.decode(&BlockHash, genesis)?;
+        let best_header = response.decode(&Header, best_header)?;
+        let runtime = response.decode(&RuntimeVersion, runtime)?;
+        let chain_nonce = response.decode(&Nonce, nonce)?;
+
+        let metadata = Metadata::at(url, runtime.spec_version).await?;
+        let indexes = T::indexes(&metadata)?;
+
+        // The best header names its parent's hash, so together with its number
+        // that is a block read in one go. A best hash and a best header asked
+        // for separately may come from different blocks.
+        let best = match best_header.number {
+            0 => (genesis_hash, 0),
+            number => (best_header.parent_hash, number - 1),
+        };
+        let (era, checkpoint, signed_at) = self.era(url, &metadata, genesis_hash, best).await?;
+        let nonce = match reserve_nonce {
+            true => NonceManager::reserve(url, &self.owner(), chain_nonce),
+            false => chain_nonce,
+        };
+
+        let params = ExtensionParams {
+            era,
+            checkpoint,
+            nonce,
+            tip: self.tip,
+            fee_asset: self.fee_asset.clone(),
+            spec_version: runtime.spec_version,
+            transaction_version: runtime.transaction_version,
             genesis_hash,
-        );
+        };
+        let extensions = match EncodedExtensions::new(&metadata, &params) {
+            Ok(extensions) => extensions,
+            Err(e) if reserve_nonce => {
+                NonceManager::reset(url, &self.owner());
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        };
 
-        let payload = (indexes, &self.call, &extra, &additional).encode();
+        let mut payload = (indexes, &self.call).encode();
+        payload.extend(&extensions.extra);
+        payload.extend(&extensions.additional);
         let signature = match payload.len() > 256 {
-            true => self.pair.sign(&sp_core::blake2_256(&payload).as_slice()),
-            false => self.pair.sign(&payload),
+            true => self.signer.sign(&sp_core::blake2_256(&payload).as_slice()),
+            false => self.signer.sign(&payload),
         };
 
-        let extrinsic = self.encode_extrinsic(signature, extra, indexes);
-        let extrinsic_hash = format!("0x{}", hex::encode(extrinsic));
-        Ok(extrinsic_hash)
+        let bytes = self.encode_extrinsic(signature, &extensions.extra, indexes);
+        Ok(SignedExtrinsic::new(bytes, nonce, era, signed_at))
+    }
+
+    /// The era, its checkpoint hash and the checkpoint's number, which is
+    /// also the block the extrinsic counts as signed at.
+    async fn era(
+        &self,
+        url: &str,
+        metadata: &Metadata,
+        genesis_hash: H256,
+        best: (H256, u64),
+    ) -> Result<(Era, H256, u64), NodeError> {
+        let (period, checkpoint) = match self.mortality {
+            Mortality::Immortal => return Ok((Era::Immortal, genesis_hash, best.1)),
+            Mortality::Mortal { period, checkpoint } => (period, checkpoint),
+        };
+
+        let (hash, number) = match checkpoint {
+            Checkpoint::Best => best,
+            Checkpoint::Finalized => {
+                let hash = FinalizedHead.get(url, None).await?;
+                let header = Header.get(url, Some(vec![format!("{:?}", hash)])).await?;
+                (hash, header.number)
+            }
+        };
+
+        let period = match period {
+            Some(period) => period,
+            None => default_period(metadata)?,
+        };
+        Ok((Era::mortal(period, number), hash, number))
     }
 
     fn encode_extrinsic(
         &self,
-        signature: sr25519::Signature,
-        extra: (Era, Compact<u32>, Compact<u128>),
+        signature: MultiSignature,
+        extra: &[u8],
         indexes: [u8; 2],
     ) -> Vec<u8> {
         let extrinsic = {
@@ -60,11 +344,10 @@ impl<T: ExtrinsicCall> Extrinsic<T> {
 
             (0b1000_0000 + 4u8).encode_to(&mut tmp);
             MultiAddress::Id::<_, u32>(self.owner()).encode_to(&mut tmp);
-            MultiSignature::Sr25519(signature).encode_to(&mut tmp);
+            signature.encode_to(&mut tmp);
 
-            extra.encode_to(&mut tmp);
-            let call = (indexes, &self.call);
-            call.encode_to(&mut tmp);
+            tmp.extend(extra);
+            RuntimeCall::with_indexes::<T>(indexes, &self.call).encode_to(&mut tmp);
 
             let len = Compact(tmp.len() as u32);
             let mut encoded = Vec::new();
@@ -76,29 +359,124 @@ impl<T: ExtrinsicCall> Extrinsic<T> {
     }
 }
 
+/// Mortal periods are powers of two, and the chain can only check a
+/// checkpoint it still keeps the hash of, so the default period never
+/// exceeds `System::BlockHashCount`.
+fn default_period(metadata: &Metadata) -> Result<u64, NodeError> {
+    let value = metadata.constant(consts::SYSTEM, consts::BLOCK_HASH_COUNT)?;
+    let block_hash_count = match value.len() {
+        8 => u64::decode(&mut &value[..]),
+        _ => u32::decode(&mut &value[..]).map(u64::from),
+    };
+
+    match block_hash_count {
+        Ok(0) => Ok(consts::DEFAULT_PERIOD),
+        Ok(count) => Ok(consts::DEFAULT_PERIOD.min(1 << count.ilog2())),
+        Err(e) => Err(NodeError::CouldNotGetConstant(e.into())),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct AttributeArgs {
+    pub did_account: AccountId32,
+    pub name: Vec<u8>,
+    pub value: Vec<u8>,
+    pub valid_for: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct AttributeNameArgs {
+    pub did_account: AccountId32,
+    pub name: Vec<u8>,
+}
+
+/// A call of any pallet encoded together with its pallet and call index, so
+/// calls of different pallets can be dispatched from one `Utility` batch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeCall(Vec<u8>);
+
+impl RuntimeCall {
+    pub fn new<T: ExtrinsicCall>(metadata: &Metadata, call: &T::Call) -> Result<Self, NodeError> {
+        Ok(RuntimeCall::with_indexes::<T>(T::indexes(metadata)?, call))
+    }
+
+    /// For indexes already looked up, or known ahead of time.
+    pub fn with_indexes<T: ExtrinsicCall>(indexes: [u8; 2], call: &T::Call) -> Self {
+        RuntimeCall((indexes, call).encode())
+    }
+}
+
+impl Encode for RuntimeCall {
+    fn size_hint(&self) -> usize {
+        self.0.len()
+    }
+
+    fn encode_to<O: Output + ?Sized>(&self, dest: &mut O) {
+        dest.write(&self.0);
+    }
+}
+
 pub struct AddAttribute {}
+pub struct UpdateAttribute {}
+pub struct ReadAttribute {}
+pub struct RemoveAttribute {}
+/// Dispatches every call or none of them.
+pub struct BatchAll {}
+/// Dispatches every call, carrying on past the ones that fail.
+pub struct ForceBatch {}
 
 pub trait ExtrinsicCall: Sized {
-    type Call: Codec;
+    type Call: Encode;
     const PALLET: &'static str;
     const METHOD: &'static str;
 
-    fn new(pair: sr25519::Pair, call: Self::Call) -> Extrinsic<Self> {
-        Extrinsic { pair, call }
+    fn new<S: Signer>(signer: S, call: Self::Call) -> Extrinsic<Self, S> {
+        Extrinsic {
+            signer,
+            call,
+            mortality: Mortality::default(),
+            tip: 0,
+            fee_asset: None,
+        }
     }
 
-    fn indexes(url: &str) -> impl std::future::Future<Output = Result<[u8; 2], NodeError>> + Send {
-        async {
-            Ok([
-                Pallet::new(Self::PALLET).get_index(url).await?,
-                Method::new(Self::METHOD).get_index(url).await?,
-            ])
-        }
+    fn indexes(metadata: &Metadata) -> Result<[u8; 2], NodeError> {
+        metadata.call_index(Self::PALLET, Self::METHOD)
     }
 }
 
 impl ExtrinsicCall for AddAttribute {
-    type Call = (AccountId32, Vec<u8>, Vec<u8>, Option<u32>);
-    const PALLET: &'static str = "PeaqDid";
+    type Call = AttributeArgs;
+    const PALLET: &'static str = did::consts::PEAQ_DID;
     const METHOD: &'static str = "add_attribute";
 }
+
+impl ExtrinsicCall for UpdateAttribute {
+    type Call = AttributeArgs;
+    const PALLET: &'static str = did::consts::PEAQ_DID;
+    const METHOD: &'static str = "update_attribute";
+}
+
+impl ExtrinsicCall for ReadAttribute {
+    type Call = AttributeNameArgs;
+    const PALLET: &'static str = did::consts::PEAQ_DID;
+    const METHOD: &'static str = "read_attribute";
+}
+
+impl ExtrinsicCall for RemoveAttribute {
+    type Call = AttributeNameArgs;
+    const PALLET: &'static str = did::consts::PEAQ_DID;
+    const METHOD: &'static str = "remove_attribute";
+}
+
+impl ExtrinsicCall for BatchAll {
+    type Call = Vec<RuntimeCall>;
+    const PALLET: &'static str = consts::UTILITY;
+    const METHOD: &'static str = "batch_all";
+}
+
+impl ExtrinsicCall for ForceBatch {
+    type Call = Vec<RuntimeCall>;
+    const PALLET: &'static str = consts::UTILITY;
+    const METHOD: &'static str = "force_batch";
+}
@@ -0,0 +1,206 @@
+use async_std::future;
+use async_std::task;
+use sp_core::H256;
+use std::time::Duration;
+
+use crate::calls::{
+    block::Block,
+    block_hash::BlockHash,
+    call::Call,
+    finalized_head::FinalizedHead,
+    header::Header,
+    subscribe_finalized_heads::{FinalizedHeads, SubscribeFinalizedHeads},
+    transport::Transport,
+};
+use crate::errors::NodeError;
+
+pub mod consts {
+    use std::time::Duration;
+
+    pub const POLL_INTERVAL: Duration = Duration::from_secs(6);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Inclusion {
+    pub block_hash: H256,
+    pub number: u64,
+    pub index: u32,
+}
+
+impl Inclusion {
+    /// Looks for `extrinsic_hash` in the best chain from block `from` through
+    /// `blocks` blocks after it, stopping early at the best block.
+    pub async fn search(
+        url: &str,
+        extrinsic_hash: H256,
+        from: u64,
+        blocks: u64,
+    ) -> Result<Option<Inclusion>, NodeError> {
+        let best = Header.get(url, None).await?.number;
+        let to = best.min(from.saturating_add(blocks));
+
+        for number in from..=to {
+            if let Some(inclusion) = Inclusion::in_block(url, number, extrinsic_hash).await? {
+                return Ok(Some(inclusion));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn in_block(
+        url: &str,
+        number: u64,
+        extrinsic_hash: H256,
+    ) -> Result<Option<Inclusion>, NodeError> {
+        let block_hash = Finality::canonical_hash(url, number).await?;
+        let block = Block
+            .get(url, Some(vec![format!("{:?}", block_hash)]))
+            .await?;
+
+        let index = block
+            .extrinsic_hashes()?
+            .iter()
+            .position(|pred| *pred == extrinsic_hash);
+        Ok(index.map(|index| Inclusion {
+            block_hash,
+            number,
+            index: index as u32,
+        }))
+    }
+}
+
+/// Finalized block numbers as they advance, from the finalized heads
+/// subscription over WebSocket and by polling the finalized head over HTTP.
+struct FinalizedNumbers {
+    url: String,
+    heads: Option<FinalizedHeads>,
+    started: bool,
+}
+
+impl FinalizedNumbers {
+    async fn new(url: &str) -> Result<FinalizedNumbers, NodeError> {
+        let heads = match Transport::from_url(url) {
+            Transport::Ws => Some(SubscribeFinalizedHeads.get(url, None).await?),
+            Transport::Http => None,
+        };
+
+        Ok(FinalizedNumbers {
+            url: url.to_string(),
+            heads,
+            started: false,
+        })
+    }
+
+    async fn next(&mut self) -> Result<u64, NodeError> {
+        if !self.started {
+            self.started = true;
+            return Finality::finalized_number(&self.url).await;
+        }
+
+        match &mut self.heads {
+            Some(heads) => match heads.next().await {
+                Some(header) => Ok(header?.number),
+                None => Err(NodeError::CouldNotReadMessageFromNode(
+                    "Subscription closed".into(),
+                )),
+            },
+            None => {
+                task::sleep(consts::POLL_INTERVAL).await;
+                Finality::finalized_number(&self.url).await
+            }
+        }
+    }
+}
+
+pub struct Finality;
+
+impl Finality {
+    pub async fn finalized_number(url: &str) -> Result<u64, NodeError> {
+        let hash = FinalizedHead.get(url, None).await?;
+        let header = Header.get(url, Some(vec![format!("{:?}", hash)])).await?;
+        Ok(header.number)
+    }
+
+    /// Whether `block_hash` is on the finalized chain. A block that was
+    /// retracted never becomes finalized, even after its number does.
+    pub async fn is_finalized(url: &str, block_hash: H256) -> Result<bool, NodeError> {
+        let number = Header
+            .get(url, Some(vec![format!("{:?}", block_hash)]))
+            .await?
+            .number;
+        if Finality::finalized_number(url).await? < number {
+            return Ok(false);
+        }
+        Ok(Finality::canonical_hash(url, number).await? == block_hash)
+    }
+
+    /// Waits until `block_hash` is finalized and returns its number.
+    pub async fn wait_for_block(
+        url: &str,
+        block_hash: H256,
+        timeout: Duration,
+    ) -> Result<u64, NodeError> {
+        let wait = async {
+            let number = Header
+                .get(url, Some(vec![format!("{:?}", block_hash)]))
+                .await?
+                .number;
+
+            let mut finalized = FinalizedNumbers::new(url).await?;
+            while finalized.next().await? < number {}
+
+            match Finality::canonical_hash(url, number).await? == block_hash {
+                true => Ok(number),
+                false => {
+                    let error = format!("{:?} was retracted", block_hash);
+                    Err(NodeError::BlockNotFinalized(error))
+                }
+            }
+        };
+
+        match future::timeout(timeout, wait).await {
+            Ok(result) => result,
+            Err(_) => {
+                let error = format!("{:?} after {:?}", block_hash, timeout);
+                Err(NodeError::BlockNotFinalized(error))
+            }
+        }
+    }
+
+    /// Waits until a block containing `extrinsic_hash` is finalized, looking
+    /// at every finalized block from number `from` onwards.
+    pub async fn wait_for_extrinsic(
+        url: &str,
+        extrinsic_hash: H256,
+        from: u64,
+        timeout: Duration,
+    ) -> Result<Inclusion, NodeError> {
+        let wait = async {
+            let mut finalized = FinalizedNumbers::new(url).await?;
+            let mut next = from;
+            loop {
+                let to = finalized.next().await?;
+                while next <= to {
+                    if let Some(inclusion) = Inclusion::in_block(url, next, extrinsic_hash).await? {
+                        return Ok(inclusion);
+                    }
+                    next += 1;
+                }
+            }
+        };
+
+        match future::timeout(timeout, wait).await {
+            Ok(result) => result,
+            Err(_) => {
+                let error = format!("{:?} after {:?}", extrinsic_hash, timeout);
+                Err(NodeError::BlockNotFinalized(error))
+            }
+        }
+    }
+
+    async fn canonical_hash(url: &str, number: u64) -> Result<H256, NodeError> {
+        BlockHash
+            .get(url, Some(vec![format!("0x{:x}", number)]))
+            .await
+    }
+}
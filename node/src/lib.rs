@@ -1,4 +1,16 @@
+pub mod blocks;
 pub mod calls;
+pub mod did;
+pub mod dispatch;
+pub mod endpoints;
 pub mod errors;
+pub mod events;
+pub mod extensions;
 pub mod extrinsics;
+pub mod finality;
 pub mod metadata;
+pub mod nonces;
+pub mod retry;
+pub mod signer;
+pub mod storage;
+pub mod value;
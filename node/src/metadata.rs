@@ -1,26 +1,411 @@
 use crate::{
-    calls::{call::Call, runtime_metadata::RuntimeMetadata},
+    calls::{
+        call::Call, runtime_metadata::RuntimeMetadata, runtime_version::RuntimeVersion,
+        state_call::StateCall,
+    },
     errors::NodeError,
 };
-use frame_metadata::v14::RuntimeMetadataV14;
-use scale_info::TypeDef::Variant;
-use std::future::Future;
+use codec::{Decode, Encode};
+use frame_metadata::v14::{self, PalletMetadata, RuntimeMetadataV14, StorageEntryMetadata};
+use frame_metadata::v15::{
+    OuterEnums, RuntimeApiMetadata, RuntimeApiMethodMetadata, RuntimeMetadataV15,
+};
+use scale_info::{form::PortableForm, TypeDef, Variant};
+use sp_core::H256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, PoisonError};
+
+pub mod consts {
+    pub const METADATA_AT_VERSION: &str = "Metadata_metadata_at_version";
+    pub const LATEST_VERSION: u32 = 15;
+}
+
+/// Type ids of the parts a signed extrinsic is encoded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtrinsicTypes {
+    pub address: u32,
+    pub call: u32,
+    pub signature: u32,
+    pub extra: u32,
+}
+
+struct Decoded {
+    version: u32,
+    metadata: RuntimeMetadataV14,
+    extrinsic: ExtrinsicTypes,
+    apis: Vec<RuntimeApiMetadata<PortableForm>>,
+    outer_enums: Option<OuterEnums<PortableForm>>,
+}
+
+impl TryFrom<frame_metadata::RuntimeMetadata> for Decoded {
+    type Error = NodeError;
+
+    fn try_from(metadata: frame_metadata::RuntimeMetadata) -> Result<Self, Self::Error> {
+        match metadata {
+            frame_metadata::RuntimeMetadata::V14(metadata) => Decoded::from_v14(metadata),
+            frame_metadata::RuntimeMetadata::V15(metadata) => Decoded::from_v15(metadata),
+            _ => Err(NodeError::CouldNotDecodeMetadataLatest),
+        }
+    }
+}
+
+impl Decoded {
+    /// V14 only names the `UncheckedExtrinsic` type, so its parts are read
+    /// from that type's parameters.
+    fn from_v14(metadata: RuntimeMetadataV14) -> Result<Self, NodeError> {
+        let params = match metadata.types.resolve(metadata.extrinsic.ty.id) {
+            Some(ty) => &ty.type_params,
+            None => {
+                let error = "UncheckedExtrinsic type is missing".to_string();
+                return Err(NodeError::CouldNotDecodeMetadataV14(error.into()));
+            }
+        };
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|pred| pred.name == name)
+                .and_then(|param| param.ty.as_ref())
+                .map(|ty| ty.id)
+        };
+
+        let extrinsic = match (
+            param("Address"),
+            param("Call"),
+            param("Signature"),
+            param("Extra"),
+        ) {
+            (Some(address), Some(call), Some(signature), Some(extra)) => ExtrinsicTypes {
+                address,
+                call,
+                signature,
+                extra,
+            },
+            _ => {
+                let error = "UncheckedExtrinsic type parameters are missing".to_string();
+                return Err(NodeError::CouldNotDecodeMetadataV14(error.into()));
+            }
+        };
+
+        Ok(Decoded {
+            version: 14,
+            metadata,
+            extrinsic,
+            apis: Vec::new(),
+            outer_enums: None,
+        })
+    }
+
+    /// Pallets, types and signed extensions are shared with V14, so the rest
+    /// of the crate keeps working on a `RuntimeMetadataV14` view. V15 names
+    /// the extrinsic's parts directly, and `Metadata::extrinsic_types` reads
+    /// them from there. The view still needs an extrinsic type: the
+    /// `UncheckedExtrinsic` built from exactly those parts, since runtimes
+    /// such as Frontier's register more than one, or else the call type.
+    fn from_v15(metadata: RuntimeMetadataV15) -> Result<Self, NodeError> {
+        let extrinsic = ExtrinsicTypes {
+            address: metadata.extrinsic.address_ty.id,
+            call: metadata.extrinsic.call_ty.id,
+            signature: metadata.extrinsic.signature_ty.id,
+            extra: metadata.extrinsic.extra_ty.id,
+        };
+        let parts = [
+            extrinsic.address,
+            extrinsic.call,
+            extrinsic.signature,
+            extrinsic.extra,
+        ];
+
+        let extrinsic_ty = metadata
+            .types
+            .types
+            .iter()
+            .find(|ty| {
+                let params = ty
+                    .ty
+                    .type_params
+                    .iter()
+                    .map(|param| param.ty.map(|ty| ty.id));
+                ty.ty.path.ident().as_deref() == Some("UncheckedExtrinsic")
+                    && params.eq(parts.map(Some))
+            })
+            .map(|ty| ty.id)
+            .unwrap_or(extrinsic.call);
+
+        let pallets = metadata
+            .pallets
+            .into_iter()
+            .map(|pallet| PalletMetadata {
+                name: pallet.name,
+                storage: pallet.storage,
+                calls: pallet.calls,
+                event: pallet.event,
+                constants: pallet.constants,
+                error: pallet.error,
+                index: pallet.index,
+            })
+            .collect();
+
+        let signed_extensions = metadata
+            .extrinsic
+            .signed_extensions
+            .into_iter()
+            .map(|extension| v14::SignedExtensionMetadata {
+                identifier: extension.identifier,
+                ty: extension.ty,
+                additional_signed: extension.additional_signed,
+            })
+            .collect();
+
+        Ok(Decoded {
+            version: 15,
+            metadata: RuntimeMetadataV14 {
+                types: metadata.types,
+                pallets,
+                extrinsic: v14::ExtrinsicMetadata {
+                    ty: extrinsic_ty.into(),
+                    version: metadata.extrinsic.version,
+                    signed_extensions,
+                },
+                ty: metadata.ty,
+            },
+            extrinsic,
+            apis: metadata.apis,
+            outer_enums: Some(metadata.outer_enums),
+        })
+    }
+}
 
-pub struct Metadata(RuntimeMetadataV14);
+struct Cached {
+    spec_version: u32,
+    decoded: Arc<Decoded>,
+}
+
+fn cache() -> MutexGuard<'static, HashMap<String, Cached>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Cached>>> = OnceLock::new();
+    CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+#[derive(Clone)]
+pub struct Metadata(Arc<Decoded>);
+
+/// Builds metadata that was decoded elsewhere, bypassing the per-node cache.
+impl TryFrom<frame_metadata::RuntimeMetadata> for Metadata {
+    type Error = NodeError;
+
+    fn try_from(metadata: frame_metadata::RuntimeMetadata) -> Result<Self, Self::Error> {
+        Ok(Metadata(Arc::new(Decoded::try_from(metadata)?)))
+    }
+}
 
 impl Metadata {
-    async fn new(url: &str) -> Result<Self, NodeError> {
-        let metadata_v14 = RuntimeMetadata.get(url, None).await?;
-        Ok(Metadata(metadata_v14))
+    pub async fn new(url: &str) -> Result<Self, NodeError> {
+        let runtime = RuntimeVersion.get(url, None).await?;
+        Metadata::at(url, runtime.spec_version).await
+    }
+
+    /// Returns the metadata of `url` for the given runtime `spec_version`,
+    /// downloading it only when the cached copy belongs to another version.
+    pub async fn at(url: &str, spec_version: u32) -> Result<Self, NodeError> {
+        Metadata::load(url, spec_version, None).await
+    }
+
+    /// Returns the metadata of the runtime that produced `block_hash`, which
+    /// differs from the current one for blocks before a runtime upgrade.
+    pub async fn at_block(url: &str, block_hash: H256) -> Result<Self, NodeError> {
+        let at = format!("{:?}", block_hash);
+        let runtime = RuntimeVersion.get(url, Some(vec![at])).await?;
+        Metadata::load(url, runtime.spec_version, Some(block_hash)).await
+    }
+
+    async fn load(url: &str, spec_version: u32, at: Option<H256>) -> Result<Self, NodeError> {
+        if let Some(metadata) = Metadata::cached(url, spec_version) {
+            return Ok(metadata);
+        }
+
+        let decoded = Arc::new(Metadata::fetch(url, at).await?);
+        let cached = Cached {
+            spec_version,
+            decoded: decoded.clone(),
+        };
+        cache().insert(url.to_string(), cached);
+        Ok(Metadata(decoded))
+    }
+
+    fn cached(url: &str, spec_version: u32) -> Option<Self> {
+        match cache().get(url) {
+            Some(cached) if cached.spec_version == spec_version => {
+                Some(Metadata(cached.decoded.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Asks for the latest version first. Only a runtime that does not have
+    /// it, or does not have the versioned metadata API at all, falls back to
+    /// the V14 `state_getMetadata` returns.
+    async fn fetch(url: &str, at: Option<H256>) -> Result<Decoded, NodeError> {
+        let at = at.map(|hash| format!("{:?}", hash));
+        let missing = |text: &str| text.contains(consts::METADATA_AT_VERSION);
+        let v14 = || RuntimeMetadata.get(url, at.clone().map(|at| vec![at]));
+
+        match Metadata::fetch_at_version(url, consts::LATEST_VERSION, at.clone()).await {
+            Ok(Some(metadata)) => Decoded::try_from(metadata),
+            Ok(None) => Decoded::try_from(v14().await?),
+            Err(NodeError::Rpc { message, data, .. })
+                if missing(&message) || data.as_deref().is_some_and(missing) =>
+            {
+                Decoded::try_from(v14().await?)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn fetch_at_version(
+        url: &str,
+        version: u32,
+        at: Option<String>,
+    ) -> Result<Option<frame_metadata::RuntimeMetadata>, NodeError> {
+        let mut params = StateCall::params(consts::METADATA_AT_VERSION, &version.encode());
+        if let (Some(params), Some(at)) = (&mut params, at) {
+            params.push(at);
+        }
+        let bytes = StateCall.get(url, params).await?;
+
+        match Option::<Vec<u8>>::decode(&mut bytes.as_slice()) {
+            Ok(Some(bytes)) => Ok(Some(RuntimeMetadata::decode(&bytes)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(NodeError::CouldNotDecodeMetadataBytes(e.into())),
+        }
+    }
+
+    pub fn version(&self) -> u32 {
+        self.0.version
     }
 
-    fn get(self) -> RuntimeMetadataV14 {
+    pub fn apis(&self) -> &[RuntimeApiMetadata<PortableForm>] {
+        &self.0.apis
+    }
+
+    pub fn runtime_api(
+        &self,
+        api: &str,
+        method: &str,
+    ) -> Option<&RuntimeApiMethodMetadata<PortableForm>> {
         self.0
+            .apis
+            .iter()
+            .find(|pred| pred.name.eq(api))?
+            .methods
+            .iter()
+            .find(|pred| pred.name.eq(method))
+    }
+
+    pub fn extrinsic_types(&self) -> ExtrinsicTypes {
+        self.0.extrinsic
+    }
+
+    pub fn outer_enums(&self) -> Option<&OuterEnums<PortableForm>> {
+        self.0.outer_enums.as_ref()
+    }
+
+    pub fn get(&self) -> &RuntimeMetadataV14 {
+        &self.0.metadata
+    }
+
+    pub fn pallet(&self, name: &str) -> Result<&PalletMetadata<PortableForm>, NodeError> {
+        match self.get().pallets.iter().find(|pred| pred.name.eq(name)) {
+            Some(pallet) => Ok(pallet),
+            None => Err(NodeError::CouldNotGetPalletIndex(name.to_string())),
+        }
+    }
+
+    pub fn constant(&self, pallet: &str, name: &str) -> Result<&[u8], NodeError> {
+        let constant = self
+            .pallet(pallet)?
+            .constants
+            .iter()
+            .find(|pred| pred.name.eq(name));
+
+        match constant {
+            Some(constant) => Ok(&constant.value),
+            None => {
+                let error = format!("{}::{}", pallet, name);
+                Err(NodeError::CouldNotGetConstant(error.into()))
+            }
+        }
+    }
+
+    /// The storage prefix of `pallet` together with its `name` entry.
+    pub fn storage_entry(
+        &self,
+        pallet: &str,
+        name: &str,
+    ) -> Result<(&str, &StorageEntryMetadata<PortableForm>), NodeError> {
+        let storage = self.pallet(pallet)?.storage.as_ref();
+        let entry =
+            storage.and_then(|storage| storage.entries.iter().find(|pred| pred.name.eq(name)));
+
+        match (storage, entry) {
+            (Some(storage), Some(entry)) => Ok((&storage.prefix, entry)),
+            _ => {
+                let error = format!("{}::{}", pallet, name);
+                Err(NodeError::CouldNotGetStorageEntry(error))
+            }
+        }
+    }
+
+    /// The name of the pallet at `index` and its error variant at `error`.
+    pub fn pallet_error(&self, index: u8, error: u8) -> Option<(&str, &Variant<PortableForm>)> {
+        let pallet = self.get().pallets.iter().find(|pred| pred.index == index)?;
+        let variant = self
+            .variants(pallet.error.as_ref()?.ty.id)?
+            .iter()
+            .find(|pred| pred.index == error)?;
+        Some((&pallet.name, variant))
+    }
+
+    pub fn variants(&self, type_id: u32) -> Option<&[Variant<PortableForm>]> {
+        match &self.get().types.resolve(type_id)?.type_def {
+            TypeDef::Variant(def) => Some(&def.variants),
+            _ => None,
+        }
+    }
+
+    /// Resolves `method` among the calls of `pallet` only, returning the
+    /// pallet index and the call index in the order they are encoded.
+    pub fn call_index(&self, pallet: &str, method: &str) -> Result<[u8; 2], NodeError> {
+        let metadata = self.pallet(pallet)?;
+        let calls = match &metadata.calls {
+            Some(calls) => calls,
+            None => {
+                let error = format!("Pallet {} has no calls", pallet);
+                return Err(NodeError::CouldNotGetMethodIndex(error));
+            }
+        };
+
+        let index = self
+            .variants(calls.ty.id)
+            .and_then(|variants| variants.iter().find(|pred| pred.name.eq(method)))
+            .map(|pred| pred.index);
+
+        match index {
+            Some(index) => Ok([metadata.index, index]),
+            None => {
+                let error = format!("Pallet {} has no call named {}", pallet, method);
+                Err(NodeError::CouldNotGetMethodIndex(error))
+            }
+        }
     }
 }
 
 pub struct Pallet(String);
-pub struct Method(String);
+pub struct Method {
+    pallet: String,
+    name: String,
+}
 
 impl Pallet {
     pub fn new(name: &str) -> Self {
@@ -29,50 +414,27 @@ impl Pallet {
 }
 
 impl Method {
-    pub fn new(name: &str) -> Self {
-        Method(name.to_string())
+    pub fn new(pallet: &str, name: &str) -> Self {
+        Method {
+            pallet: pallet.to_string(),
+            name: name.to_string(),
+        }
     }
 }
 
 pub trait Searchable: Sized {
-    fn get_index(&self, url: &str) -> impl Future<Output = Result<u8, NodeError>> + Send;
+    fn get_index(&self, metadata: &Metadata) -> Result<u8, NodeError>;
 }
 
 impl Searchable for Pallet {
-    async fn get_index(&self, url: &str) -> Result<u8, NodeError> {
-        let index = Metadata::new(url)
-            .await?
-            .get()
-            .pallets
-            .iter()
-            .find(|pred| pred.name.eq(&self.0))
-            .map(|pred| pred.index);
-
-        match index {
-            Some(index) => Ok(index),
-            None => Err(NodeError::CouldNotGetPalletIndex(self.0.clone())),
-        }
+    fn get_index(&self, metadata: &Metadata) -> Result<u8, NodeError> {
+        Ok(metadata.pallet(&self.0)?.index)
     }
 }
 
 impl Searchable for Method {
-    async fn get_index(&self, url: &str) -> Result<u8, NodeError> {
-        let metadata = Metadata::new(url).await?.get().types.types;
-        let mut iter = metadata.iter();
-
-        while let Some(item) = iter.next() {
-            if let Variant(res) = &item.ty.type_def {
-                let index = res
-                    .variants
-                    .iter()
-                    .find(|pred| pred.name.eq(&self.0))
-                    .map(|pred| pred.index);
-                if let Some(index) = index {
-                    return Ok(index);
-                }
-            }
-        }
-
-        Err(NodeError::CouldNotGetPalletIndex(self.0.clone()))
+    fn get_index(&self, metadata: &Metadata) -> Result<u8, NodeError> {
+        let [_, method] = metadata.call_index(&self.pallet, &self.name)?;
+        Ok(method)
     }
 }
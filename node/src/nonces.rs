@@ -0,0 +1,68 @@
+use crate::calls::{call::Call, nonce::Nonce};
+use crate::errors::NodeError;
+use sp_core::crypto::AccountId32;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+type Key = (String, AccountId32);
+
+fn nonces() -> MutexGuard<'static, HashMap<Key, u32>> {
+    static NONCES: OnceLock<Mutex<HashMap<Key, u32>>> = OnceLock::new();
+    NONCES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Hands out nonces per node url and account without asking the node every
+/// time, so several transactions of one account can be in flight at once.
+pub struct NonceManager;
+
+impl NonceManager {
+    pub async fn next(url: &str, account: &AccountId32) -> Result<u32, NodeError> {
+        let key = (url.to_string(), account.clone());
+        if let Some(next) = nonces().get_mut(&key) {
+            *next += 1;
+            return Ok(*next - 1);
+        }
+
+        let synced = Nonce.get(url, Some(vec![account.to_string()])).await?;
+        Ok(NonceManager::reserve(url, account, synced))
+    }
+
+    /// Reserves the next nonce given the chain's `synced` view of it, which
+    /// counts the pool's transactions too. The local nonce wins while it is
+    /// ahead, as it is whenever our own transactions are still on their way
+    /// to the pool. When the chain is ahead, something else used the account
+    /// and every local nonce from here on would be stale, so it catches up
+    /// instead of failing a transaction first.
+    pub fn reserve(url: &str, account: &AccountId32, synced: u32) -> u32 {
+        let mut nonces = nonces();
+        let next = nonces
+            .entry((url.to_string(), account.clone()))
+            .or_insert(synced);
+        *next = (*next).max(synced) + 1;
+        *next - 1
+    }
+
+    /// Forgets the local nonce if the chain never reached `nonce`, for a
+    /// transaction the pool accepted but that can no longer be included.
+    /// Every later local nonce would otherwise wait behind the gap for good.
+    pub async fn release_unused(
+        url: &str,
+        account: &AccountId32,
+        nonce: u32,
+    ) -> Result<(), NodeError> {
+        let synced = Nonce.get(url, Some(vec![account.to_string()])).await?;
+        if synced <= nonce {
+            NonceManager::reset(url, account);
+        }
+        Ok(())
+    }
+
+    /// Forgets the local nonce so that the next call syncs with the chain
+    /// again. Call this after a transaction failed to reach the pool.
+    pub fn reset(url: &str, account: &AccountId32) {
+        nonces().remove(&(url.to_string(), account.clone()));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::errors::NodeError;
+use async_std::task;
+use std::future::Future;
+use std::time::Duration;
+
+pub mod consts {
+    use std::time::Duration;
+
+    pub const MAX_ATTEMPTS: u32 = 4;
+    pub const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(8);
+}
+
+/// Retries transient network failures and rate limiting with exponential
+/// backoff. Anything else is returned from the first attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: consts::MAX_ATTEMPTS,
+            initial_backoff: consts::INITIAL_BACKOFF,
+            max_backoff: consts::MAX_BACKOFF,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn never() -> Self {
+        RetryPolicy::default().max_attempts(1)
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// The policy that gives up first, for operations made of several calls.
+    pub fn strictest(self, other: RetryPolicy) -> Self {
+        match other.max_attempts < self.max_attempts {
+            true => other,
+            false => self,
+        }
+    }
+
+    /// Delay before the attempt that follows `attempt`, doubling each time.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    pub fn should_retry(&self, error: &NodeError, attempt: u32) -> bool {
+        attempt < self.max_attempts && error.is_retryable()
+    }
+
+    pub async fn run<T, F, Fut>(&self, mut operation: F) -> Result<T, NodeError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, NodeError>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(e) if self.should_retry(&e, attempt) => {}
+                result => return result,
+            }
+            task::sleep(self.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
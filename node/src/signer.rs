@@ -0,0 +1,43 @@
+use sp_core::crypto::AccountId32;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+use sp_runtime::traits::IdentifyAccount;
+use sp_runtime::{MultiSignature, MultiSigner};
+
+/// A key that can sign extrinsics. The account id and the `MultiSignature`
+/// variant always match the key's scheme.
+pub trait Signer {
+    fn account_id(&self) -> AccountId32;
+    fn sign(&self, payload: &[u8]) -> MultiSignature;
+}
+
+impl Signer for sr25519::Pair {
+    fn account_id(&self) -> AccountId32 {
+        MultiSigner::from(self.public()).into_account()
+    }
+
+    fn sign(&self, payload: &[u8]) -> MultiSignature {
+        MultiSignature::Sr25519(Pair::sign(self, payload))
+    }
+}
+
+impl Signer for ed25519::Pair {
+    fn account_id(&self) -> AccountId32 {
+        MultiSigner::from(self.public()).into_account()
+    }
+
+    fn sign(&self, payload: &[u8]) -> MultiSignature {
+        MultiSignature::Ed25519(Pair::sign(self, payload))
+    }
+}
+
+/// The account of an ecdsa key is the blake2-256 hash of its compressed
+/// public key.
+impl Signer for ecdsa::Pair {
+    fn account_id(&self) -> AccountId32 {
+        MultiSigner::from(self.public()).into_account()
+    }
+
+    fn sign(&self, payload: &[u8]) -> MultiSignature {
+        MultiSignature::Ecdsa(Pair::sign(self, payload))
+    }
+}
@@ -0,0 +1,214 @@
+use crate::calls::{batch::Batch, call::Call, keys_paged::KeysPaged, storage::Storage};
+use crate::errors::NodeError;
+use crate::metadata::Metadata;
+use crate::value::Value;
+use codec::{Decode, Encode};
+use frame_metadata::v14::{
+    StorageEntryMetadata, StorageEntryModifier, StorageEntryType, StorageHasher,
+};
+use scale_info::form::PortableForm;
+use sp_core::hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64};
+use sp_core::H256;
+
+pub mod consts {
+    pub const PAGE_SIZE: u32 = 256;
+}
+
+/// A storage item addressed by pallet and item name plus its typed keys. The
+/// key is hashed with the hashers the runtime declares for the item, and the
+/// value is decoded either into a known Rust type or by its metadata type.
+#[derive(Clone)]
+pub struct StorageQuery {
+    metadata: Metadata,
+    pallet: String,
+    prefix: String,
+    entry: StorageEntryMetadata<PortableForm>,
+    keys: Vec<Vec<u8>>,
+    at: Option<H256>,
+}
+
+impl StorageQuery {
+    pub fn new(metadata: &Metadata, pallet: &str, item: &str) -> Result<Self, NodeError> {
+        let (prefix, entry) = metadata.storage_entry(pallet, item)?;
+        Ok(StorageQuery {
+            metadata: metadata.clone(),
+            pallet: pallet.to_string(),
+            prefix: prefix.to_string(),
+            entry: entry.clone(),
+            keys: Vec::new(),
+            at: None,
+        })
+    }
+
+    pub fn key<K: Encode>(mut self, key: &K) -> Self {
+        self.keys.push(key.encode());
+        self
+    }
+
+    /// Reads the value as of `block_hash` instead of the best block.
+    pub fn at(mut self, block_hash: H256) -> Self {
+        self.at = Some(block_hash);
+        self
+    }
+
+    pub fn hashers(&self) -> &[StorageHasher] {
+        match &self.entry.ty {
+            StorageEntryType::Plain(_) => &[],
+            StorageEntryType::Map { hashers, .. } => hashers,
+        }
+    }
+
+    pub fn value_type(&self) -> u32 {
+        match &self.entry.ty {
+            StorageEntryType::Plain(ty) => ty.id,
+            StorageEntryType::Map { value, .. } => value.id,
+        }
+    }
+
+    /// The storage key for the keys given so far. With fewer keys than the
+    /// item has hashers this is a prefix shared by every matching entry.
+    pub fn storage_key(&self) -> Result<Vec<u8>, NodeError> {
+        let hashers = self.hashers();
+        if self.keys.len() > hashers.len() {
+            let error = format!(
+                "{}::{} takes {} keys, got {}",
+                self.pallet,
+                self.entry.name,
+                hashers.len(),
+                self.keys.len()
+            );
+            return Err(NodeError::CouldNotGetStorageEntry(error));
+        }
+
+        Ok(key(&self.prefix, &self.entry.name, hashers, &self.keys))
+    }
+
+    /// Raw value bytes, falling back to the item's default when the runtime
+    /// declares one and nothing is stored.
+    pub async fn fetch_raw(&self, url: &str) -> Result<Option<Vec<u8>>, NodeError> {
+        if self.keys.len() != self.hashers().len() {
+            let error = format!("{}::{} needs every key", self.pallet, self.entry.name);
+            return Err(NodeError::CouldNotGetStorageEntry(error));
+        }
+
+        let mut params = vec![format!("0x{}", hex::encode(self.storage_key()?))];
+        if let Some(at) = self.at {
+            params.push(format!("{:?}", at));
+        }
+        let value = match Storage.get(url, Some(params)).await? {
+            Some(value) => value,
+            None => {
+                return match self.entry.modifier {
+                    StorageEntryModifier::Default => Ok(Some(self.entry.default.clone())),
+                    StorageEntryModifier::Optional => Ok(None),
+                };
+            }
+        };
+
+        match hex::decode(value.trim_start_matches("0x")) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) => Err(NodeError::CouldNotGetStorageValue(e.into())),
+        }
+    }
+
+    /// Every stored entry under the keys given so far, as raw key and value
+    /// bytes, fetched a page of keys at a time as of the same block.
+    pub async fn entries_raw(&self, url: &str) -> Result<Vec<(Vec<u8>, Vec<u8>)>, NodeError> {
+        let prefix = self.storage_key()?;
+        let mut entries = Vec::new();
+        let mut start_key: Option<Vec<u8>> = None;
+
+        loop {
+            let keys = KeysPaged::new(&prefix, consts::PAGE_SIZE, start_key.as_deref())
+                .at(self.at)
+                .get(url, None)
+                .await?;
+            if keys.is_empty() {
+                return Ok(entries);
+            }
+
+            let mut batch = Batch::new();
+            for key in &keys {
+                let mut params = vec![format!("0x{}", hex::encode(key))];
+                if let Some(at) = self.at {
+                    params.push(format!("{:?}", at));
+                }
+                batch.push(&Storage, Some(params));
+            }
+            let response = batch.send(url).await?;
+
+            for (slot, key) in keys.iter().enumerate() {
+                let value = match response.decode(&Storage, slot)? {
+                    Some(value) => value,
+                    None => continue,
+                };
+                match hex::decode(value.trim_start_matches("0x")) {
+                    Ok(value) => entries.push((key.clone(), value)),
+                    Err(e) => return Err(NodeError::CouldNotGetStorageValue(e.into())),
+                }
+            }
+
+            if keys.len() < consts::PAGE_SIZE as usize {
+                return Ok(entries);
+            }
+            start_key = keys.last().cloned();
+        }
+    }
+
+    pub async fn entries<V: Decode>(&self, url: &str) -> Result<Vec<(Vec<u8>, V)>, NodeError> {
+        let mut entries = Vec::new();
+        for (key, value) in self.entries_raw(url).await? {
+            match V::decode(&mut value.as_slice()) {
+                Ok(value) => entries.push((key, value)),
+                Err(e) => return Err(NodeError::CouldNotDecodeValue(e.into())),
+            }
+        }
+        Ok(entries)
+    }
+
+    pub async fn fetch<V: Decode>(&self, url: &str) -> Result<Option<V>, NodeError> {
+        let bytes = match self.fetch_raw(url).await? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        match V::decode(&mut bytes.as_slice()) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => Err(NodeError::CouldNotDecodeValue(e.into())),
+        }
+    }
+
+    pub async fn fetch_value(&self, url: &str) -> Result<Option<Value>, NodeError> {
+        let bytes = match self.fetch_raw(url).await? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        let registry = &self.metadata.get().types;
+        let value = Value::decode(registry, self.value_type(), &mut bytes.as_slice())?;
+        Ok(Some(value))
+    }
+}
+
+/// The key of `item` in the storage of pallet `prefix`: both names hashed
+/// with twox128, then each encoded key hashed with its hasher.
+pub fn key(prefix: &str, item: &str, hashers: &[StorageHasher], keys: &[Vec<u8>]) -> Vec<u8> {
+    let mut key = twox_128(prefix.as_bytes()).to_vec();
+    key.extend(twox_128(item.as_bytes()));
+    for (hasher, encoded) in hashers.iter().zip(keys) {
+        key.extend(hash(hasher, encoded));
+    }
+    key
+}
+
+pub fn hash(hasher: &StorageHasher, encoded: &[u8]) -> Vec<u8> {
+    match hasher {
+        StorageHasher::Blake2_128 => blake2_128(encoded).to_vec(),
+        StorageHasher::Blake2_256 => blake2_256(encoded).to_vec(),
+        StorageHasher::Blake2_128Concat => [&blake2_128(encoded)[..], encoded].concat(),
+        StorageHasher::Twox128 => twox_128(encoded).to_vec(),
+        StorageHasher::Twox256 => twox_256(encoded).to_vec(),
+        StorageHasher::Twox64Concat => [&twox_64(encoded)[..], encoded].concat(),
+        StorageHasher::Identity => encoded.to_vec(),
+    }
+}
@@ -0,0 +1,207 @@
+use crate::errors::NodeError;
+use codec::{Compact, Decode, Input};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+
+/// A SCALE value decoded by walking its type in the runtime's type registry,
+/// for chain state whose Rust type this crate does not know.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    Char(char),
+    String(String),
+    Unsigned(u128),
+    Signed(i128),
+    U256([u8; 32]),
+    I256([u8; 32]),
+    Bytes(Vec<u8>),
+    Sequence(Vec<Value>),
+    Composite(Vec<(Option<String>, Value)>),
+    Variant {
+        name: String,
+        index: u8,
+        fields: Vec<(Option<String>, Value)>,
+    },
+    BitSequence(Vec<u8>),
+}
+
+impl Value {
+    pub fn decode(
+        registry: &PortableRegistry,
+        type_id: u32,
+        input: &mut &[u8],
+    ) -> Result<Value, NodeError> {
+        match decode_type(registry, type_id, input) {
+            Ok(value) => Ok(value),
+            Err(e) => Err(NodeError::CouldNotDecodeValue(e.into())),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Value> {
+        let fields = match self {
+            Value::Composite(fields) => fields,
+            Value::Variant { fields, .. } => fields,
+            _ => return None,
+        };
+
+        fields
+            .iter()
+            .find(|(pred, _)| pred.as_deref() == Some(name))
+            .map(|(_, value)| value)
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match self {
+            Value::Unsigned(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Value::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn decode_type(
+    registry: &PortableRegistry,
+    type_id: u32,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    let ty = match registry.resolve(type_id) {
+        Some(ty) => ty,
+        None => return Err("Type is missing from the registry".into()),
+    };
+
+    match &ty.type_def {
+        TypeDef::Composite(def) => Ok(Value::Composite(decode_fields(
+            registry,
+            &def.fields,
+            input,
+        )?)),
+        TypeDef::Variant(def) => {
+            let index = u8::decode(input)?;
+            let variant = match def.variants.iter().find(|pred| pred.index == index) {
+                Some(variant) => variant,
+                None => return Err("Variant index is out of range".into()),
+            };
+
+            Ok(Value::Variant {
+                name: variant.name.clone(),
+                index,
+                fields: decode_fields(registry, &variant.fields, input)?,
+            })
+        }
+        TypeDef::Sequence(def) => {
+            let len = Compact::<u32>::decode(input)?.0;
+            decode_items(registry, def.type_param.id, len, input)
+        }
+        TypeDef::Array(def) => decode_items(registry, def.type_param.id, def.len, input),
+        TypeDef::Tuple(def) => {
+            let mut fields = Vec::new();
+            for field in &def.fields {
+                fields.push((None, decode_type(registry, field.id, input)?));
+            }
+            Ok(Value::Composite(fields))
+        }
+        TypeDef::Primitive(def) => decode_primitive(def, input),
+        TypeDef::Compact(def) => {
+            let value = Value::Unsigned(Compact::<u128>::decode(input)?.0);
+            match registry.resolve(def.type_param.id).map(|ty| &ty.type_def) {
+                Some(TypeDef::Composite(inner)) if inner.fields.len() == 1 => {
+                    let name = inner.fields[0].name.clone();
+                    Ok(Value::Composite(vec![(name, value)]))
+                }
+                _ => Ok(value),
+            }
+        }
+        TypeDef::BitSequence(def) => {
+            let bits = Compact::<u32>::decode(input)?.0 as usize;
+            let store = match registry
+                .resolve(def.bit_store_type.id)
+                .map(|ty| &ty.type_def)
+            {
+                Some(TypeDef::Primitive(TypeDefPrimitive::U16)) => 2,
+                Some(TypeDef::Primitive(TypeDefPrimitive::U32)) => 4,
+                Some(TypeDef::Primitive(TypeDefPrimitive::U64)) => 8,
+                _ => 1,
+            };
+            let len = bits.div_ceil(store * 8) * store;
+            Ok(Value::BitSequence(read_bytes(input, len)?))
+        }
+    }
+}
+
+fn decode_fields(
+    registry: &PortableRegistry,
+    fields: &[Field<PortableForm>],
+    input: &mut &[u8],
+) -> Result<Vec<(Option<String>, Value)>, codec::Error> {
+    let mut values = Vec::new();
+    for field in fields {
+        values.push((
+            field.name.clone(),
+            decode_type(registry, field.ty.id, input)?,
+        ));
+    }
+    Ok(values)
+}
+
+fn decode_items(
+    registry: &PortableRegistry,
+    type_id: u32,
+    len: u32,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    let is_byte = matches!(
+        registry.resolve(type_id).map(|ty| &ty.type_def),
+        Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+    );
+    if is_byte {
+        return Ok(Value::Bytes(read_bytes(input, len as usize)?));
+    }
+
+    let mut items = Vec::new();
+    for _ in 0..len {
+        items.push(decode_type(registry, type_id, input)?);
+    }
+    Ok(Value::Sequence(items))
+}
+
+fn decode_primitive(
+    primitive: &TypeDefPrimitive,
+    input: &mut &[u8],
+) -> Result<Value, codec::Error> {
+    let value = match primitive {
+        TypeDefPrimitive::Bool => Value::Bool(bool::decode(input)?),
+        TypeDefPrimitive::Char => match char::from_u32(u32::decode(input)?) {
+            Some(char) => Value::Char(char),
+            None => return Err("Invalid char".into()),
+        },
+        TypeDefPrimitive::Str => Value::String(String::decode(input)?),
+        TypeDefPrimitive::U8 => Value::Unsigned(u8::decode(input)?.into()),
+        TypeDefPrimitive::U16 => Value::Unsigned(u16::decode(input)?.into()),
+        TypeDefPrimitive::U32 => Value::Unsigned(u32::decode(input)?.into()),
+        TypeDefPrimitive::U64 => Value::Unsigned(u64::decode(input)?.into()),
+        TypeDefPrimitive::U128 => Value::Unsigned(u128::decode(input)?),
+        TypeDefPrimitive::U256 => Value::U256(<[u8; 32]>::decode(input)?),
+        TypeDefPrimitive::I8 => Value::Signed(i8::decode(input)?.into()),
+        TypeDefPrimitive::I16 => Value::Signed(i16::decode(input)?.into()),
+        TypeDefPrimitive::I32 => Value::Signed(i32::decode(input)?.into()),
+        TypeDefPrimitive::I64 => Value::Signed(i64::decode(input)?.into()),
+        TypeDefPrimitive::I128 => Value::Signed(i128::decode(input)?),
+        TypeDefPrimitive::I256 => Value::I256(<[u8; 32]>::decode(input)?),
+    };
+    Ok(value)
+}
+
+fn read_bytes(input: &mut &[u8], len: usize) -> Result<Vec<u8>, codec::Error> {
+    if input.len() < len {
+        return Err("Not enough data to fill buffer".into());
+    }
+
+    let mut bytes = vec![0u8; len];
+    input.read(&mut bytes)?;
+    Ok(bytes)
+}
@@ -0,0 +1,51 @@
+use codec::Encode;
+use common::{attribute_args, ADD_ATTRIBUTE};
+use node::extrinsics::{AddAttribute, BatchAll, ExtrinsicCall, ForceBatch, RuntimeCall};
+
+mod common;
+
+// Fixed indexes, in the order each pallet declares its calls
+const REMARK: [u8; 2] = [0x00, 0];
+const BATCH_ALL: [u8; 2] = [0x28, 2];
+const FORCE_BATCH: [u8; 2] = [0x28, 4];
+
+struct Remark {}
+
+impl ExtrinsicCall for Remark {
+    type Call = Vec<u8>;
+    const PALLET: &'static str = "System";
+    const METHOD: &'static str = "remark";
+}
+
+fn calls() -> Vec<RuntimeCall> {
+    vec![
+        RuntimeCall::with_indexes::<AddAttribute>(ADD_ATTRIBUTE, &attribute_args(None)),
+        RuntimeCall::with_indexes::<Remark>(REMARK, &b"hi".to_vec()),
+    ]
+}
+
+fn inner_calls() -> Vec<u8> {
+    // Two calls, each led by its own pallet and call index
+    let mut expected = vec![0x08];
+    expected.extend([0x2a, 0x00]);
+    expected.extend([7u8; 32]);
+    expected.extend([0x10, b't', b'e', b'm', b'p', 0x08, b'2', b'1', 0x00]);
+    expected.extend([0x00, 0x00, 0x08, b'h', b'i']);
+    expected
+}
+
+#[test]
+fn batch_all_mixes_pallets() {
+    let call = RuntimeCall::with_indexes::<BatchAll>(BATCH_ALL, &calls());
+
+    let expected = [vec![0x28, 0x02], inner_calls()].concat();
+    assert_eq!(call.encode(), expected);
+}
+
+#[test]
+fn force_batch_mixes_pallets() {
+    let call = RuntimeCall::with_indexes::<ForceBatch>(FORCE_BATCH, &calls());
+
+    let expected = [vec![0x28, 0x04], inner_calls()].concat();
+    assert_eq!(call.encode(), expected);
+}
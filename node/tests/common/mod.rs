@@ -0,0 +1,32 @@
+// Each test crate only uses part of the fixture
+#![allow(dead_code)]
+
+use node::extrinsics::{AttributeArgs, AttributeNameArgs};
+use sp_core::crypto::AccountId32;
+
+// Fixed indexes, in the order the pallet declares its calls
+pub const PEAQ_DID: u8 = 0x2a;
+pub const ADD_ATTRIBUTE: [u8; 2] = [PEAQ_DID, 0];
+pub const UPDATE_ATTRIBUTE: [u8; 2] = [PEAQ_DID, 1];
+pub const READ_ATTRIBUTE: [u8; 2] = [PEAQ_DID, 2];
+pub const REMOVE_ATTRIBUTE: [u8; 2] = [PEAQ_DID, 3];
+
+pub fn did_account() -> AccountId32 {
+    AccountId32::from([7u8; 32])
+}
+
+pub fn name_args() -> AttributeNameArgs {
+    AttributeNameArgs {
+        did_account: did_account(),
+        name: b"temp".to_vec(),
+    }
+}
+
+pub fn attribute_args(valid_for: Option<u32>) -> AttributeArgs {
+    AttributeArgs {
+        did_account: did_account(),
+        name: b"temp".to_vec(),
+        value: b"21".to_vec(),
+        valid_for,
+    }
+}
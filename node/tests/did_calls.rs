@@ -0,0 +1,76 @@
+use codec::Encode;
+use common::{
+    attribute_args, name_args, ADD_ATTRIBUTE, READ_ATTRIBUTE, REMOVE_ATTRIBUTE, UPDATE_ATTRIBUTE,
+};
+use node::extrinsics::{
+    AddAttribute, ReadAttribute, RemoveAttribute, RuntimeCall, UpdateAttribute,
+};
+
+mod common;
+
+#[test]
+fn attribute_args_encode_in_call_order() {
+    let mut expected = vec![7u8; 32];
+    expected.extend([0x10, b't', b'e', b'm', b'p']);
+    expected.extend([0x08, b'2', b'1']);
+    expected.extend([0x01, 100, 0, 0, 0]);
+
+    assert_eq!(attribute_args(Some(100)).encode(), expected);
+}
+
+#[test]
+fn attribute_args_encode_missing_validity_as_none() {
+    let encoded = attribute_args(None).encode();
+
+    assert_eq!(encoded.last(), Some(&0x00));
+    assert_eq!(encoded.len(), 32 + 5 + 3 + 1);
+}
+
+#[test]
+fn attribute_name_args_encode_in_call_order() {
+    let mut expected = vec![7u8; 32];
+    expected.extend([0x10, b't', b'e', b'm', b'p']);
+    assert_eq!(name_args().encode(), expected);
+}
+
+#[test]
+fn add_attribute_call_encoding() {
+    let call = RuntimeCall::with_indexes::<AddAttribute>(ADD_ATTRIBUTE, &attribute_args(None));
+
+    let mut expected = vec![0x2a, 0x00];
+    expected.extend([7u8; 32]);
+    expected.extend([0x10, b't', b'e', b'm', b'p', 0x08, b'2', b'1', 0x00]);
+    assert_eq!(call.encode(), expected);
+}
+
+#[test]
+fn update_attribute_call_encoding() {
+    let call =
+        RuntimeCall::with_indexes::<UpdateAttribute>(UPDATE_ATTRIBUTE, &attribute_args(Some(100)));
+
+    let mut expected = vec![0x2a, 0x01];
+    expected.extend([7u8; 32]);
+    expected.extend([0x10, b't', b'e', b'm', b'p', 0x08, b'2', b'1']);
+    expected.extend([0x01, 0x64, 0x00, 0x00, 0x00]);
+    assert_eq!(call.encode(), expected);
+}
+
+#[test]
+fn read_attribute_call_encoding() {
+    let call = RuntimeCall::with_indexes::<ReadAttribute>(READ_ATTRIBUTE, &name_args());
+
+    let mut expected = vec![0x2a, 0x02];
+    expected.extend([7u8; 32]);
+    expected.extend([0x10, b't', b'e', b'm', b'p']);
+    assert_eq!(call.encode(), expected);
+}
+
+#[test]
+fn remove_attribute_call_encoding() {
+    let call = RuntimeCall::with_indexes::<RemoveAttribute>(REMOVE_ATTRIBUTE, &name_args());
+
+    let mut expected = vec![0x2a, 0x03];
+    expected.extend([7u8; 32]);
+    expected.extend([0x10, b't', b'e', b'm', b'p']);
+    assert_eq!(call.encode(), expected);
+}
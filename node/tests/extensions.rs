@@ -0,0 +1,135 @@
+use frame_metadata::v14::{ExtrinsicMetadata, RuntimeMetadataV14, SignedExtensionMetadata};
+use frame_metadata::RuntimeMetadata;
+use node::errors::NodeError;
+use node::extensions::{consts, EncodedExtensions, ExtensionParams};
+use node::metadata::Metadata;
+use scale_info::{meta_type, TypeInfo};
+use sp_core::H256;
+use sp_runtime::generic::Era;
+use std::marker::PhantomData;
+
+// V14 metadata only names the extrinsic type, its parts are its parameters
+#[derive(TypeInfo)]
+struct UncheckedExtrinsic<Address, Call, Signature, Extra>(
+    PhantomData<(Address, Call, Signature, Extra)>,
+);
+
+fn metadata(extensions: &[&'static str]) -> Metadata {
+    let signed_extensions = extensions
+        .iter()
+        .map(|&identifier| SignedExtensionMetadata {
+            identifier,
+            ty: meta_type::<()>(),
+            additional_signed: meta_type::<()>(),
+        })
+        .collect();
+    let extrinsic = ExtrinsicMetadata {
+        ty: meta_type::<UncheckedExtrinsic<u32, u8, u64, ()>>(),
+        version: 4,
+        signed_extensions,
+    };
+    let runtime = RuntimeMetadataV14::new(Vec::new(), extrinsic, meta_type::<()>());
+    Metadata::try_from(RuntimeMetadata::V14(runtime)).unwrap()
+}
+
+fn params(fee_asset: Option<Vec<u8>>) -> ExtensionParams {
+    ExtensionParams {
+        // Period 64, phase 36
+        era: Era::mortal(64, 100),
+        checkpoint: H256::repeat_byte(0x11),
+        nonce: 5,
+        tip: 1,
+        fee_asset,
+        spec_version: 100,
+        transaction_version: 2,
+        genesis_hash: H256::repeat_byte(0x22),
+    }
+}
+
+#[test]
+fn extensions_encode_in_runtime_order() {
+    let metadata = metadata(&[
+        consts::CHECK_NON_ZERO_SENDER,
+        consts::CHECK_SPEC_VERSION,
+        consts::CHECK_TX_VERSION,
+        consts::CHECK_GENESIS,
+        consts::CHECK_MORTALITY,
+        consts::CHECK_NONCE,
+        consts::CHECK_WEIGHT,
+        consts::CHARGE_TRANSACTION_PAYMENT,
+    ]);
+    let encoded = EncodedExtensions::new(&metadata, &params(None)).unwrap();
+
+    assert_eq!(encoded.extra, vec![0x45, 0x02, 0x14, 0x04]);
+    let mut additional = vec![100, 0, 0, 0, 2, 0, 0, 0];
+    additional.extend([0x22; 32]);
+    additional.extend([0x11; 32]);
+    assert_eq!(encoded.additional, additional);
+}
+
+#[test]
+fn reordered_extensions_follow_the_runtime() {
+    let metadata = metadata(&[
+        consts::CHARGE_TRANSACTION_PAYMENT,
+        consts::CHECK_NONCE,
+        consts::CHECK_ERA,
+        consts::CHECK_GENESIS,
+        consts::CHECK_SPEC_VERSION,
+    ]);
+    let encoded = EncodedExtensions::new(&metadata, &params(None)).unwrap();
+
+    assert_eq!(encoded.extra, vec![0x04, 0x14, 0x45, 0x02]);
+    let mut additional = vec![0x11; 32];
+    additional.extend([0x22; 32]);
+    additional.extend([100, 0, 0, 0]);
+    assert_eq!(encoded.additional, additional);
+}
+
+#[test]
+fn metadata_hash_check_is_disabled() {
+    let metadata = metadata(&[consts::CHECK_METADATA_HASH]);
+    let encoded = EncodedExtensions::new(&metadata, &params(None)).unwrap();
+
+    // Mode::Disabled, then no metadata hash
+    assert_eq!(encoded.extra, vec![0x00]);
+    assert_eq!(encoded.additional, vec![0x00]);
+}
+
+#[test]
+fn asset_payment_without_an_asset_pays_native() {
+    let metadata = metadata(&[consts::CHARGE_ASSET_TX_PAYMENT]);
+    let encoded = EncodedExtensions::new(&metadata, &params(None)).unwrap();
+
+    assert_eq!(encoded.extra, vec![0x04, 0x00]);
+    assert!(encoded.additional.is_empty());
+}
+
+#[test]
+fn asset_payment_appends_the_encoded_asset() {
+    let metadata = metadata(&[consts::CHARGE_ASSET_TX_PAYMENT]);
+    let encoded = EncodedExtensions::new(&metadata, &params(Some(vec![7, 0, 0, 0]))).unwrap();
+
+    assert_eq!(encoded.extra, vec![0x04, 0x01, 7, 0, 0, 0]);
+    assert!(encoded.additional.is_empty());
+}
+
+#[test]
+fn unknown_extensions_are_unsupported() {
+    let metadata = metadata(&[consts::CHECK_NONCE, "CheckUnknown"]);
+    let error = EncodedExtensions::new(&metadata, &params(None)).unwrap_err();
+
+    assert!(
+        matches!(error, NodeError::UnsupportedSignedExtension(ref name) if name == "CheckUnknown")
+    );
+}
+
+#[test]
+fn fee_asset_needs_asset_payment() {
+    let metadata = metadata(&[consts::CHARGE_TRANSACTION_PAYMENT]);
+    let error = EncodedExtensions::new(&metadata, &params(Some(vec![7, 0, 0, 0]))).unwrap_err();
+
+    assert!(matches!(
+        error,
+        NodeError::UnsupportedSignedExtension(ref name) if name == consts::CHARGE_ASSET_TX_PAYMENT
+    ));
+}
@@ -0,0 +1,46 @@
+use node::blocks::Header;
+use sp_runtime::DigestItem;
+
+fn json(logs: &[&str]) -> serde_json::Value {
+    serde_json::json!({
+        "parentHash": format!("0x{}", "11".repeat(32)),
+        "number": "0x1a2b",
+        "stateRoot": format!("0x{}", "22".repeat(32)),
+        "extrinsicsRoot": format!("0x{}", "33".repeat(32)),
+        "digest": { "logs": logs },
+    })
+}
+
+fn header(logs: &[&str]) -> Header {
+    serde_json::from_value(json(logs)).unwrap()
+}
+
+#[test]
+fn numbers_are_hex() {
+    assert_eq!(header(&[]).number, 0x1a2b);
+}
+
+#[test]
+fn pre_runtime_digest_is_decoded() {
+    let header = header(&["0x0661757261200100000000000000"]);
+
+    let pre_runtime = header.digest.pre_runtime();
+    assert_eq!(
+        pre_runtime,
+        Some((*b"aura", &[1u8, 0, 0, 0, 0, 0, 0, 0][..]))
+    );
+    assert_eq!(header.digest.seal(), None);
+}
+
+#[test]
+fn undecodable_logs_keep_their_bytes() {
+    let header = header(&["0x09ff", "0x0661757261200100000000000000"]);
+
+    assert_eq!(header.digest.logs[0], DigestItem::Other(vec![0x09, 0xff]));
+    assert!(header.digest.pre_runtime().is_some());
+}
+
+#[test]
+fn logs_that_are_not_hex_fail() {
+    assert!(serde_json::from_value::<Header>(json(&["not hex"])).is_err());
+}
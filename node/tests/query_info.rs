@@ -0,0 +1,50 @@
+use node::calls::query_info::{DispatchClass, DispatchInfo, Weight};
+
+fn dispatch_info(partial_fee: &str) -> serde_json::Result<DispatchInfo> {
+    let json = format!(
+        r#"{{"weight":{{"refTime":100,"proofSize":20}},"class":"normal","partialFee":{}}}"#,
+        partial_fee
+    );
+    serde_json::from_str(&json)
+}
+
+#[test]
+fn fee_as_a_number() {
+    let info = dispatch_info("125000000").unwrap();
+
+    assert_eq!(info.partial_fee, 125_000_000);
+    assert_eq!(
+        info.weight,
+        Weight {
+            ref_time: 100,
+            proof_size: 20
+        }
+    );
+    assert_eq!(info.class, DispatchClass::Normal);
+}
+
+#[test]
+fn fee_as_a_decimal_string() {
+    // Above u64::MAX, which only fits the string forms
+    let info = dispatch_info(r#""18446744073709551616""#).unwrap();
+
+    assert_eq!(info.partial_fee, 1 << 64);
+}
+
+#[test]
+fn fee_as_hex() {
+    let info = dispatch_info(r#""0x10000000000000000""#).unwrap();
+
+    assert_eq!(info.partial_fee, 1 << 64);
+    assert_eq!(
+        dispatch_info(r#""0x7735940""#).unwrap().partial_fee,
+        125_000_000
+    );
+}
+
+#[test]
+fn fee_that_is_not_a_balance_fails() {
+    assert!(dispatch_info(r#""0xzz""#).is_err());
+    assert!(dispatch_info(r#""12.5""#).is_err());
+    assert!(dispatch_info("-1").is_err());
+}
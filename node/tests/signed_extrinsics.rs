@@ -0,0 +1,37 @@
+use node::extrinsics::SignedExtrinsic;
+use sp_core::H256;
+use sp_runtime::generic::Era;
+
+fn h256(hex: &str) -> H256 {
+    H256::from_slice(&hex::decode(hex).unwrap())
+}
+
+#[test]
+fn hash_covers_the_length_prefix() {
+    // An unsigned v4 System.remark("hi"), led by its compact length of 6
+    let bytes = vec![0x18, 0x04, 0x00, 0x00, 0x08, b'h', b'i'];
+    let signed = SignedExtrinsic::new(bytes, 0, Era::Immortal, 0);
+
+    let expected = h256("32b3367533e8c455b1d6be8038df1b36bd9dc581548740af65709a920e181e64");
+    assert_eq!(signed.hash, expected);
+    // Not the hash of the body alone
+    assert_ne!(
+        signed.hash,
+        h256("d644c1f2a4986fa38ed801355a6f741a33e39ace6a70f6a57ff449abc06fbe75")
+    );
+}
+
+#[test]
+fn hash_is_blake2_256() {
+    let signed = SignedExtrinsic::new(b"abc".to_vec(), 0, Era::Immortal, 0);
+
+    let expected = h256("bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319");
+    assert_eq!(signed.hash, expected);
+}
+
+#[test]
+fn hex_is_the_submitted_form() {
+    let signed = SignedExtrinsic::new(vec![0x18, 0x04], 0, Era::mortal(64, 100), 100);
+
+    assert_eq!(signed.hex(), "0x1804");
+}
@@ -0,0 +1,84 @@
+use codec::Encode;
+use frame_metadata::v14::StorageHasher;
+use node::storage::{hash, key};
+use sp_keyring::AccountKeyring;
+
+fn bytes(hex: &str) -> Vec<u8> {
+    hex::decode(hex.trim_start_matches("0x")).unwrap()
+}
+
+#[test]
+fn plain_items_hash_both_names() {
+    let expected = bytes("0xf0c365c3cf59d671eb72da0e7a4113c49f1f0515f462cdcf84e0f1d6045dfcbb");
+
+    assert_eq!(key("Timestamp", "Now", &[], &[]), expected);
+}
+
+#[test]
+fn system_account_of_alice() {
+    let alice = AccountKeyring::Alice.to_account_id();
+    let expected = bytes(concat!(
+        "0x26aa394eea5630e07c48ae0c9558cef7b99d880ec681799c0cf30e8886371da9",
+        "de1e86a9a8c739864cf3cc5ec2bea59f",
+        "d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d",
+    ));
+
+    let hashers = [StorageHasher::Blake2_128Concat];
+    assert_eq!(
+        key("System", "Account", &hashers, &[alice.encode()]),
+        expected
+    );
+}
+
+#[test]
+fn system_block_hash_of_genesis() {
+    let expected = bytes(concat!(
+        "0x26aa394eea5630e07c48ae0c9558cef7a44704b568d21667356a5a050c118746",
+        "b4def25cfda6ef3a00000000",
+    ));
+
+    let hashers = [StorageHasher::Twox64Concat];
+    assert_eq!(
+        key("System", "BlockHash", &hashers, &[0u32.encode()]),
+        expected
+    );
+}
+
+#[test]
+fn keys_beyond_the_hashers_are_ignored() {
+    let hashers = [StorageHasher::Identity];
+    let keys = [vec![1, 2], vec![3]];
+
+    assert_eq!(key("A", "B", &hashers, &keys)[32..], [1, 2]);
+}
+
+#[test]
+fn hashers_match_known_vectors() {
+    let cases: [(StorageHasher, &[u8], &str); 5] = [
+        (
+            StorageHasher::Twox128,
+            b"System",
+            "0x26aa394eea5630e07c48ae0c9558cef7",
+        ),
+        (
+            StorageHasher::Twox64Concat,
+            b"System",
+            "0x26aa394eea5630e053797374656d",
+        ),
+        (StorageHasher::Identity, b"System", "0x53797374656d"),
+        (
+            StorageHasher::Blake2_128,
+            b"",
+            "0xcae66941d9efbd404e4d88758ea67670",
+        ),
+        (
+            StorageHasher::Blake2_256,
+            b"",
+            "0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+        ),
+    ];
+
+    for (hasher, input, expected) in cases {
+        assert_eq!(hash(&hasher, input), bytes(expected), "{:?}", hasher);
+    }
+}
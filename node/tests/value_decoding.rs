@@ -0,0 +1,162 @@
+use codec::Encode;
+use node::value::Value;
+use scale_info::{meta_type, PortableRegistry, Registry, TypeInfo};
+
+#[derive(Encode, TypeInfo)]
+struct AccountData {
+    free: u128,
+    reserved: u128,
+}
+
+#[derive(Encode, TypeInfo)]
+struct AccountInfo {
+    nonce: u32,
+    providers: u32,
+    data: AccountData,
+}
+
+#[derive(Encode, TypeInfo)]
+enum Attribute {
+    Empty,
+    Named {
+        name: Vec<u8>,
+        valid_for: Option<u32>,
+    },
+    Indexed(#[codec(compact)] u64, bool),
+}
+
+#[derive(Encode, TypeInfo)]
+struct Wrapped(#[codec(compact)] u32);
+
+fn registry<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+    let mut registry = Registry::new();
+    let id = registry.register_type(&meta_type::<T>()).id;
+    (registry.into(), id)
+}
+
+fn decode<T: TypeInfo + 'static>(bytes: &[u8]) -> Value {
+    let (registry, id) = registry::<T>();
+    let input = &mut &bytes[..];
+    let value = Value::decode(&registry, id, input).unwrap();
+    assert!(input.is_empty(), "{} bytes left", input.len());
+    value
+}
+
+#[test]
+fn composites_keep_field_names() {
+    let bytes = [
+        &5u32.to_le_bytes()[..],
+        &1u32.to_le_bytes(),
+        &1_000u128.to_le_bytes(),
+        &7u128.to_le_bytes(),
+    ]
+    .concat();
+
+    let expected = Value::Composite(vec![
+        (Some("nonce".into()), Value::Unsigned(5)),
+        (Some("providers".into()), Value::Unsigned(1)),
+        (
+            Some("data".into()),
+            Value::Composite(vec![
+                (Some("free".into()), Value::Unsigned(1_000)),
+                (Some("reserved".into()), Value::Unsigned(7)),
+            ]),
+        ),
+    ]);
+    let value = decode::<AccountInfo>(&bytes);
+    assert_eq!(value, expected);
+    assert_eq!(
+        value.field("data").and_then(|data| data.field("free")),
+        Some(&Value::Unsigned(1_000))
+    );
+}
+
+#[test]
+fn variants_decode_by_index() {
+    let bytes = Attribute::Named {
+        name: b"hi".to_vec(),
+        valid_for: Some(42),
+    }
+    .encode();
+
+    let expected = Value::Variant {
+        name: "Named".into(),
+        index: 1,
+        fields: vec![
+            (Some("name".into()), Value::Bytes(b"hi".to_vec())),
+            (
+                Some("valid_for".into()),
+                Value::Variant {
+                    name: "Some".into(),
+                    index: 1,
+                    fields: vec![(None, Value::Unsigned(42))],
+                },
+            ),
+        ],
+    };
+    assert_eq!(
+        bytes,
+        [0x01, 0x08, b'h', b'i', 0x01, 0x2a, 0x00, 0x00, 0x00]
+    );
+    assert_eq!(decode::<Attribute>(&bytes), expected);
+    assert_eq!(
+        decode::<Attribute>(&Attribute::Empty.encode()),
+        Value::Variant {
+            name: "Empty".into(),
+            index: 0,
+            fields: vec![],
+        }
+    );
+}
+
+#[test]
+fn compact_fields_and_tuples() {
+    let bytes = Attribute::Indexed(1_000_000, true).encode();
+
+    let expected = Value::Variant {
+        name: "Indexed".into(),
+        index: 2,
+        fields: vec![
+            (None, Value::Unsigned(1_000_000)),
+            (None, Value::Bool(true)),
+        ],
+    };
+    assert_eq!(bytes, [0x02, 0x02, 0x09, 0x3d, 0x00, 0x01]);
+    assert_eq!(decode::<Attribute>(&bytes), expected);
+    assert_eq!(
+        decode::<(u8, String)>(&[0x09, 0x08, b'o', b'k']),
+        Value::Composite(vec![
+            (None, Value::Unsigned(9)),
+            (None, Value::String("ok".into())),
+        ])
+    );
+}
+
+#[test]
+fn compact_newtypes_keep_their_wrapper() {
+    assert_eq!(
+        decode::<Wrapped>(&Wrapped(300).encode()),
+        Value::Composite(vec![(None, Value::Unsigned(300))])
+    );
+}
+
+#[test]
+fn sequences_of_non_bytes_decode_item_by_item() {
+    let bytes = vec![1u16, 2, 3].encode();
+
+    let expected = Value::Sequence(vec![
+        Value::Unsigned(1),
+        Value::Unsigned(2),
+        Value::Unsigned(3),
+    ]);
+    assert_eq!(decode::<Vec<u16>>(&bytes), expected);
+    assert_eq!(decode::<[u8; 3]>(&[4, 5, 6]), Value::Bytes(vec![4, 5, 6]));
+}
+
+#[test]
+fn unknown_variants_and_short_input_fail() {
+    let (registry, id) = registry::<Attribute>();
+
+    assert!(Value::decode(&registry, id, &mut &[0x03][..]).is_err());
+    assert!(Value::decode(&registry, id, &mut &[0x01, 0x08, b'h'][..]).is_err());
+}
@@ -1,4 +1,13 @@
-pub async fn run(url: &str) -> anyhow::Result<()> {
-    hub::run(url).await?;
+use node::endpoints::Endpoints;
+
+pub async fn run(
+    url: &str,
+    fallbacks: Vec<String>,
+    tip: u128,
+    fee_budget: Option<u128>,
+    dry_run: bool,
+) -> anyhow::Result<()> {
+    Endpoints::register(url, fallbacks);
+    hub::run(url, tip, fee_budget, dry_run).await?;
     Ok(())
 }
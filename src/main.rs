@@ -18,12 +18,27 @@ async fn main() {
     println!("Network: {}", config.network);
     println!("Host: {}", config.host);
     println!("Port: {}", config.port);
+    println!("Tip: {}", config.tip);
+    println!("Fallbacks: {}", config.fallbacks.join(", "));
+    match config.fee_budget {
+        Some(fee_budget) => println!("Fee budget: {}", fee_budget),
+        None => println!("Fee budget: none"),
+    }
+    println!("Dry run: {}", config.dry_run);
 
     println!("Listening to IoT sensors...");
 
     let url = format!("{}:{}", config.host, config.port);
 
-    if let Err(e) = run(&url).await {
+    if let Err(e) = run(
+        &url,
+        config.fallbacks,
+        config.tip,
+        config.fee_budget,
+        config.dry_run,
+    )
+    .await
+    {
         eprintln!("Application error: {}", e);
         process::exit(1);
     }